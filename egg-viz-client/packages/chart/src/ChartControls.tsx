@@ -20,7 +20,7 @@ import {
   useState,
 } from "react";
 import { useTables } from "./Fetch";
-import { PivotTable2, setIntersect } from "./DataProcessing";
+import { PivotTable2, groupByRecorder, setIntersect } from "./DataProcessing";
 import { type UseQueryResult } from "@tanstack/react-query";
 import { HoverTooltip } from "./hooks";
 import {
@@ -320,7 +320,7 @@ function ChartControlColumns({}: ChartControlProps) {
   const setCtrls = useContext(ChartDispatchContext);
 
   const columnValues = useTables({
-    select: useCallback((table: PivotTable2) => table.value_names, []),
+    select: useCallback((table: PivotTable2) => table.numeric_names(), []),
     combine: useCallback(
       (queries: UseQueryResult<string[]>[]) =>
         queries
@@ -350,11 +350,7 @@ function ChartControlColumns({}: ChartControlProps) {
           value={ctrls.columns.x}
         >
           <option value="index">Index</option>
-          {columnValues.map((v) => (
-            <option key={`x-${v}`} value={v}>
-              {v}
-            </option>
-          ))}
+          <ValueOptions names={columnValues} axis="x" />
         </ChartSelect>
       </div>
       <div className="space-x-2 w-max">
@@ -368,17 +364,33 @@ function ChartControlColumns({}: ChartControlProps) {
           value={ctrls.columns.y}
         >
           <option value="index">Index</option>
-          {columnValues.map((v) => (
-            <option key={`y-${v}`} value={v}>
-              {v}
-            </option>
-          ))}
+          <ValueOptions names={columnValues} axis="y" />
         </ChartSelect>
       </div>
     </ChartControlItem>
   );
 }
 
+function ValueOptions({ names, axis }: { names: string[]; axis: string }) {
+  return groupByRecorder(names).map(([group, values]) =>
+    group === null ? (
+      values.map((v) => (
+        <option key={`${axis}-${v}`} value={v}>
+          {v}
+        </option>
+      ))
+    ) : (
+      <optgroup key={`${axis}-${group}`} label={group}>
+        {values.map((v) => (
+          <option key={`${axis}-${v}`} value={v}>
+            {v.slice(group.length + 1)}
+          </option>
+        ))}
+      </optgroup>
+    ),
+  );
+}
+
 function ChartControlScale({}: ChartControlProps) {
   const ctrls = useContext(ChartOptionsContext);
   const setCtrls = useContext(ChartDispatchContext);
@@ -74,20 +74,52 @@ export function mkObject(keys: string[], values: any[]) {
   return obj;
 }
 
+const NUMERIC_TYPES = ["int", "float", "duration"];
+
+// groups the values of multi-valued recorders, which are named
+// `<recorder>/<name>`, under their recorder. Other values have no group.
+export function groupByRecorder(names: string[]): [string | null, string[]][] {
+  const groups = new Map<string | null, string[]>();
+  for (const name of names) {
+    const slash = name.indexOf("/");
+    const group = slash > 0 ? name.slice(0, slash) : null;
+    groups.set(group, [...(groups.get(group) ?? []), name]);
+  }
+  return [...groups.entries()];
+}
+
 export class PivotTable2 {
   file_id: number;
   value_names: string[];
+  value_types: Record<string, string>;
+  warnings: string[];
   data: aq.ColumnTable;
 
-  constructor(file_id: number, parsed: aq.ColumnTable) {
+  constructor(
+    file_id: number,
+    parsed: aq.ColumnTable,
+    value_types?: Record<string, string>,
+    warnings?: string[],
+  ) {
     this.file_id = file_id;
+    this.warnings = warnings ?? [];
     this.value_names = parsed.select("name").dedupe().array("name") as string[];
+    this.value_types = value_types ?? {};
     this.data = parsed
       .groupby(
         parsed.columnNames().filter((n) => n !== "name" && n !== "value"),
       )
       .pivot("name", "value");
   }
+
+  // value names that can be plotted on a numeric axis. Names without a known
+  // type are kept so that older servers keep working.
+  numeric_names(): string[] {
+    return this.value_names.filter((name) => {
+      const typ = this.value_types[name];
+      return typ === undefined || NUMERIC_TYPES.includes(typ);
+    });
+  }
 }
 
 export class PivotTable {
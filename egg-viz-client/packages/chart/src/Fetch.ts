@@ -41,11 +41,18 @@ export interface DownloadResponse {
   rows: string[][];
 }
 
+export interface HeaderResponse {
+  path: string;
+  headers: string[];
+  types: Record<string, string>;
+  warnings: string[];
+}
+
 async function fetchFileId(
   port: string,
   file_id: number,
 ): Promise<PivotTable2> {
-  const headers = await fetch(
+  const headers: HeaderResponse = await fetch(
     `http://localhost:${port}/download_headers/${file_id}`,
   ).then((res) => res.json());
 
@@ -55,6 +62,8 @@ async function fetchFileId(
       header: false,
       names: headers.headers,
     }),
+    headers.types,
+    headers.warnings,
   );
 }
 
@@ -112,3 +121,50 @@ function byKey<T, U>(keyFn: (x: T) => U): (a: T, b: T) => 1 | 0 | -1 {
     }
   };
 }
+
+export interface SnapshotsResponse {
+  iterations: number[];
+}
+
+export function useSnapshots(file_id: number): UseQueryResult<number[]> {
+  const serverConfig = useContext(ServerConfigContext);
+  return useQuery({
+    queryKey: ["snapshots", file_id],
+    queryFn: async () =>
+      await fetch(
+        `http://localhost:${serverConfig?.port ?? "8080"}/snapshots/${file_id}`,
+      )
+        .then(throwResponseError)
+        .then((res) => res.json())
+        .then((data: SnapshotsResponse) => data.iterations),
+    staleTime: 1000 * 30,
+  });
+}
+
+// the `egraph-serialize` json format
+export interface SerializedEGraph {
+  nodes: Record<
+    string,
+    { op: string; children: string[]; eclass: string; cost: number }
+  >;
+  root_eclasses: string[];
+  class_data: Record<string, { type?: string }>;
+}
+
+export function useSnapshot(
+  file_id: number,
+  iteration: number | null,
+): UseQueryResult<SerializedEGraph> {
+  const serverConfig = useContext(ServerConfigContext);
+  return useQuery({
+    queryKey: ["snapshot", file_id, iteration],
+    queryFn: async () =>
+      await fetch(
+        `http://localhost:${serverConfig?.port ?? "8080"}/snapshot/${file_id}/${iteration}`,
+      )
+        .then(throwResponseError)
+        .then((res) => res.json()),
+    enabled: iteration !== null,
+    staleTime: Infinity,
+  });
+}
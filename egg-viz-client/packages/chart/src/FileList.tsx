@@ -9,8 +9,10 @@ import {
   useState,
 } from "react";
 import usePersistState from "./usePersistState";
-import { type ASet, PivotTable2 } from "./DataProcessing";
-import { useKnownFiles, useTables } from "./Fetch";
+import { type ASet, PivotTable2, groupByRecorder } from "./DataProcessing";
+import { useKnownFiles, useSnapshots, useTables } from "./Fetch";
+import { SnapshotGraph } from "./Snapshot";
+import { Heatmap, histogramNames } from "./Heatmap";
 import { type UseQueryResult } from "@tanstack/react-query";
 import { PiWaveSineBold } from "react-icons/pi";
 import { IoRemoveOutline } from "react-icons/io5";
@@ -77,11 +79,41 @@ function FileItemLoaded({
     return [...uniqueRules];
   }, [table]);
 
+  // why the run stopped, when the log has a `run_end` footer
+  const stopReason: string | undefined = useMemo(() => {
+    if (!table) return undefined;
+    const footer = table.data
+      .filter((d) => d.when === "run_end")
+      .objects() as any[];
+    return footer.length > 0 ? footer[0]["stop_reason"] : undefined;
+  }, [table]);
+
   const [selRule, setSelRul] = usePersistState<number | null>(
     null,
     `file-item-sel-rule-${table.file_id}`,
   );
 
+  const histograms = useMemo(() => histogramNames(table), [table]);
+
+  // every point at which `BestExpr` saw the best program change
+  const bestChanges: [number, string, string][] = useMemo(() => {
+    if (!table || !table.value_names.includes("best_program/changed")) {
+      return [];
+    }
+    return (table.data.objects() as any[])
+      .filter((d) => String(d["best_program/changed"]) === "true")
+      .map((d) => [
+        d["iteration"],
+        d["when"] === "iteration_end"
+          ? "end of iteration"
+          : `before ${d["rule_name"]}`,
+        d["best_program/expr"],
+      ]);
+  }, [table]);
+
+  const snapshots = useSnapshots(table.file_id);
+  const [selSnapshot, setSelSnapshot] = useState<number | null>(null);
+
   const selRef = useRef<HTMLButtonElement | null>(null);
 
   useEffect(() => {
@@ -145,13 +177,32 @@ function FileItemLoaded({
           </div>
         </button>
       )}
+      {table?.warnings.map((warning, idx) => (
+        <div
+          key={idx}
+          className="flex flex-row items-center gap-x-1 text-sm text-red-800 dark:text-red-400"
+        >
+          <fa6.FaTriangleExclamation className="shrink-0" />
+          {warning}
+        </div>
+      ))}
+      {stopReason && (
+        <div className="font-bold dark:text-white">
+          Stopped: <span className="font-normal font-mono">{stopReason}</span>
+        </div>
+      )}
       {table && (
         <>
           <div className="font-bold dark:text-white">Value names:</div>
           <div className="flex flex-row gap-[2px] overflow-x-auto no-scrollbar">
-            {table.value_names.map((value, idx) => (
+            {groupByRecorder(table.value_names).flatMap(([group, values]) =>
+              group === null
+                ? values.map((value) => [value, value])
+                : [[`${group} (${values.length})`, values.join("\n")]],
+            ).map(([value, title], idx) => (
               <div
                 key={idx}
+                title={title}
                 className={[
                   "border-2",
                   "rounded-full",
@@ -170,6 +221,61 @@ function FileItemLoaded({
           </div>{" "}
         </>
       )}
+      {histograms.map((name) => (
+        <div key={name}>
+          <div className="font-bold dark:text-white">{name}:</div>
+          <div className="overflow-x-auto">
+            <Heatmap table={table} name={name} />
+          </div>
+        </div>
+      ))}
+      {bestChanges.length > 0 && (
+        <>
+          <div className="font-bold dark:text-white">Best program changed:</div>
+          <div className="max-h-32 overflow-auto font-mono text-xs dark:text-white">
+            {bestChanges.map(([iteration, at, expr], idx) => (
+              <div key={idx} className="text-nowrap">
+                <span className="font-bold">
+                  {iteration}, {at}:
+                </span>{" "}
+                {expr}
+              </div>
+            ))}
+          </div>
+        </>
+      )}
+      {snapshots.data && snapshots.data.length > 0 && (
+        <>
+          <div className="font-bold dark:text-white">Snapshots:</div>
+          <div className="flex flex-row gap-[2px] overflow-x-auto no-scrollbar">
+            {snapshots.data.map((iteration) => (
+              <button
+                key={iteration}
+                className={[
+                  "border-2",
+                  "rounded-full",
+                  "border-egg-500 dark:border-mixed-80",
+                  selSnapshot === iteration
+                    ? "bg-egg-500 dark:bg-mixed-80"
+                    : "bg-egg-400 dark:bg-mixed-60",
+                  "dark:text-white",
+                  "px-1",
+                  "font-mono text-xs",
+                ].join(" ")}
+                onClick={(e) => {
+                  e.stopPropagation();
+                  setSelSnapshot(selSnapshot === iteration ? null : iteration);
+                }}
+              >
+                {iteration}
+              </button>
+            ))}
+          </div>
+          {selSnapshot !== null && (
+            <SnapshotGraph file_id={table.file_id} iteration={selSnapshot} />
+          )}
+        </>
+      )}
       <div className="font-bold dark:text-white">
         {ruleList?.length} {ruleList?.length > 1 ? "rules" : "rule"} used:
       </div>
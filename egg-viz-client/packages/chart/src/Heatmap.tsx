@@ -0,0 +1,75 @@
+import * as d3 from "d3";
+import { useMemo } from "react";
+import { PivotTable2 } from "./DataProcessing";
+
+// the histograms recorded at the end of every iteration, such as
+// `eclass_sizes/histogram`
+export function histogramNames(table: PivotTable2): string[] {
+  return table.value_names.filter(
+    (name) =>
+      (name === "histogram" || name.endsWith("/histogram")) &&
+      table.value_types[name] === "list" &&
+      histogramSeries(table, name).length > 0,
+  );
+}
+
+function histogramSeries(
+  table: PivotTable2,
+  name: string,
+): [number, number[]][] {
+  const rows = table.data
+    .filter((d) => d.when === "iteration_end")
+    .objects() as any[];
+  return rows.flatMap((row) => {
+    try {
+      const histogram = JSON.parse(row[name]);
+      return Array.isArray(histogram)
+        ? [[Number(row.iteration), histogram as number[]]]
+        : [];
+    } catch {
+      return [];
+    }
+  });
+}
+
+const CELL = 8;
+
+// one column per iteration, one row per bucket, darker cells hold more
+export function Heatmap({
+  table,
+  name,
+}: {
+  table: PivotTable2;
+  name: string;
+}) {
+  const series = useMemo(() => histogramSeries(table, name), [table, name]);
+  const buckets = d3.max(series, ([_, h]) => h.length) ?? 0;
+  const color = d3
+    .scaleSequentialLog(d3.interpolateYlOrBr)
+    .domain([1, d3.max(series, ([_, h]) => d3.max(h)) ?? 1]);
+
+  return (
+    <svg
+      width={series.length * CELL}
+      height={buckets * CELL}
+      className="rounded-sm bg-egg-300 dark:bg-mixed-40"
+    >
+      {series.map(([iteration, histogram], x) =>
+        histogram.map((count, bucket) => (
+          <rect
+            key={`${x}-${bucket}`}
+            x={x * CELL}
+            y={(buckets - bucket - 1) * CELL}
+            width={CELL}
+            height={CELL}
+            fill={count > 0 ? color(count) : "transparent"}
+          >
+            <title>
+              {`iteration ${iteration}: ${count} with ${2 ** bucket}-${2 ** (bucket + 1) - 1}`}
+            </title>
+          </rect>
+        )),
+      )}
+    </svg>
+  );
+}
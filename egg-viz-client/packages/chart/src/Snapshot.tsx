@@ -0,0 +1,132 @@
+import * as d3 from "d3";
+import { useEffect, useMemo, useRef } from "react";
+import { type SerializedEGraph, useSnapshot } from "./Fetch";
+
+interface ClassNode extends d3.SimulationNodeDatum {
+  id: string;
+  ops: string[];
+  root: boolean;
+  typ?: string;
+}
+
+type ClassLink = d3.SimulationLinkDatum<ClassNode>;
+
+// collapses e-nodes into their e-classes, linking a class to the classes of
+// its nodes' children
+function classGraph(egraph: SerializedEGraph): [ClassNode[], ClassLink[]] {
+  const classes = new Map<string, ClassNode>();
+  for (const node of Object.values(egraph.nodes)) {
+    const cls = classes.get(node.eclass) ?? {
+      id: node.eclass,
+      ops: [],
+      root: egraph.root_eclasses.includes(node.eclass),
+      typ: egraph.class_data[node.eclass]?.type,
+    };
+    cls.ops.push(node.op);
+    classes.set(node.eclass, cls);
+  }
+
+  const links = new Map<string, ClassLink>();
+  for (const node of Object.values(egraph.nodes)) {
+    for (const child of node.children) {
+      const target = egraph.nodes[child]?.eclass;
+      if (target === undefined) continue;
+      links.set(`${node.eclass}->${target}`, {
+        source: node.eclass,
+        target,
+      });
+    }
+  }
+
+  return [[...classes.values()], [...links.values()]];
+}
+
+export function SnapshotGraph({
+  file_id,
+  iteration,
+}: {
+  file_id: number;
+  iteration: number;
+}) {
+  const snapshot = useSnapshot(file_id, iteration);
+  const svgRef = useRef<SVGSVGElement | null>(null);
+  const graph = useMemo(
+    () => snapshot.data && classGraph(snapshot.data),
+    [snapshot.data],
+  );
+
+  useEffect(() => {
+    if (!graph || !svgRef.current) return;
+    const [nodes, links] = graph;
+    const svg = d3.select(svgRef.current);
+    svg.selectAll("*").remove();
+
+    const link = svg
+      .append("g")
+      .attr("class", "stroke-egg-700 dark:stroke-mixed-80")
+      .selectAll("line")
+      .data(links)
+      .join("line");
+
+    const node = svg
+      .append("g")
+      .selectAll("circle")
+      .data(nodes)
+      .join("circle")
+      .attr("r", (d) => 3 + Math.sqrt(d.ops.length))
+      .attr("class", (d) =>
+        d.root ? "fill-red-800 dark:fill-red-400" : "fill-egg-500",
+      );
+    node
+      .append("title")
+      .text(
+        (d) =>
+          `e-class ${d.id}${d.typ ? ` (${d.typ})` : ""}\n${d.ops.join("\n")}`,
+      );
+
+    const { width, height } = svgRef.current.getBoundingClientRect();
+    const simulation = d3
+      .forceSimulation(nodes)
+      .force(
+        "link",
+        d3.forceLink<ClassNode, ClassLink>(links).id((d) => d.id),
+      )
+      .force("charge", d3.forceManyBody().strength(-8))
+      .force("center", d3.forceCenter(width / 2, height / 2))
+      .on("tick", () => {
+        link
+          .attr("x1", (d) => (d.source as ClassNode).x ?? 0)
+          .attr("y1", (d) => (d.source as ClassNode).y ?? 0)
+          .attr("x2", (d) => (d.target as ClassNode).x ?? 0)
+          .attr("y2", (d) => (d.target as ClassNode).y ?? 0);
+        node.attr("cx", (d) => d.x ?? 0).attr("cy", (d) => d.y ?? 0);
+      });
+
+    return () => {
+      simulation.stop();
+    };
+  }, [graph]);
+
+  if (snapshot.isPending) {
+    return (
+      <div className="text-sm animate-subtle-pulse dark:text-white">
+        Loading snapshot
+      </div>
+    );
+  }
+
+  if (snapshot.error) {
+    return (
+      <div className="text-sm text-red-800 dark:text-red-400">
+        Could not load snapshot
+      </div>
+    );
+  }
+
+  return (
+    <svg
+      ref={svgRef}
+      className="w-full h-48 rounded-md bg-egg-300 dark:bg-mixed-40"
+    />
+  );
+}
@@ -3,16 +3,47 @@ use warp::{
     reply::{json, Reply},
 };
 
-use std::path::PathBuf;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use egg_stats::{read::Log, FORMAT_VERSION};
 
-use crate::KnownFiles;
+use crate::{
+    watcher::{parse_records, value_types, Row},
+    HasExtension, KnownFiles,
+};
 
-type Row = Vec<String>;
+/// The columns of every log row, in the order they are served as csv.
+const HEADERS: [&str; 7] = [
+    "id",
+    "iteration",
+    "rule_name",
+    "rule",
+    "when",
+    "name",
+    "value",
+];
+
+const LEGACY_WARNING: &str = "this log was written before egg-stats versioned its logs; \
+     its after_search rows are labelled before_search";
 
 #[derive(serde::Serialize, Debug)]
 pub struct HeaderResponse {
     path: PathBuf,
     headers: Vec<String>,
+    /// the value type of each `name` in the file
+    types: HashMap<String, String>,
+    /// problems with the file worth telling the user about
+    warnings: Vec<String>,
+}
+
+fn read_log(file_id: usize, known_files: &KnownFiles, root: &Path) -> Option<(PathBuf, Log)> {
+    let path = known_files.get_path(file_id).ok()?;
+    let contents = std::fs::read(root.join(&path)).ok()?;
+    let records = parse_records(&contents, path.has_extension("jsonl"));
+    Some((path, Log { records }))
 }
 
 pub async fn header_handler(
@@ -20,27 +51,22 @@ pub async fn header_handler(
     known_files: KnownFiles,
     root: PathBuf,
 ) -> Result<impl Reply, Rejection> {
-    let path = known_files
-        .get_path(file_id)
-        .map_err(|_| reject::not_found())?
-        .clone();
-
-    let mut rdr = csv::ReaderBuilder::new()
-        .has_headers(true)
-        .delimiter(b',')
-        .from_path(root.join(&path))
-        .map_err(|_| reject::not_found())?;
+    let (path, log) = read_log(file_id, &known_files, &root).ok_or_else(reject::not_found)?;
 
-    let headers: Vec<String> = rdr
-        .headers()
-        .map_err(|_| reject::reject())?
-        .into_iter()
-        .map(|s| s.to_string())
-        .collect();
+    let mut warnings = vec![];
+    if !log.records.is_empty() && log.format_version() < FORMAT_VERSION {
+        warnings.push(LEGACY_WARNING.to_string());
+    }
+    for warning in &warnings {
+        println!("  {path:?}: {warning}");
+    }
 
-    let response = HeaderResponse { path, headers };
-
-    Ok(json(&response))
+    Ok(json(&HeaderResponse {
+        path,
+        headers: HEADERS.iter().map(|s| s.to_string()).collect(),
+        types: value_types(log.records.iter()),
+        warnings,
+    }))
 }
 
 pub async fn body_handler(
@@ -48,22 +74,19 @@ pub async fn body_handler(
     known_files: KnownFiles,
     root: PathBuf,
 ) -> Result<impl Reply, Rejection> {
-    let path = known_files
-        .get_path(file_id)
-        .map_err(|_| reject::not_found())?
-        .clone();
-
-    let mut rdr = csv::ReaderBuilder::new()
-        .has_headers(true)
-        .delimiter(b',')
-        .from_path(root.join(&path))
-        .map_err(|_| reject::not_found())?;
+    let (_path, log) = read_log(file_id, &known_files, &root).ok_or_else(reject::not_found)?;
 
-    let rows = rdr
-        .deserialize::<Row>()
-        .flatten()
-        .map(|row| row.join(","))
-        .collect::<Vec<_>>();
+    // every log is served as quoted csv, so that the client only has to deal
+    // with one format and fields containing commas, quotes or newlines
+    // survive the trip
+    let mut wtr = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(vec![]);
+    for record in log.records {
+        wtr.serialize(Row::from(record))
+            .map_err(|_| reject::reject())?;
+    }
 
-    Ok(rows.join("\n"))
+    let body = wtr.into_inner().map_err(|_| reject::reject())?;
+    String::from_utf8(body).map_err(|_| reject::reject())
 }
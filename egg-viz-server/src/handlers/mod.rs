@@ -1,3 +1,4 @@
 pub mod available;
 pub mod download;
+pub mod snapshot;
 pub mod ws;
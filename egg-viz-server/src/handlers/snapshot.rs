@@ -0,0 +1,62 @@
+use std::path::{Path, PathBuf};
+
+use itertools::Itertools;
+use warp::{
+    http::header::CONTENT_TYPE,
+    reject::{self, Rejection},
+    reply::{json, with_header, Reply},
+};
+
+use crate::{HasExtension, KnownFiles};
+
+#[derive(serde::Serialize)]
+struct SnapshotsResponse {
+    /// the iterations that have a snapshot, in order
+    iterations: Vec<usize>,
+}
+
+/// Snapshots of a log live in a `<log>.snapshots` directory beside it, one
+/// `iteration-<n>.json` file per snapshot.
+fn snapshot_dir(file_id: usize, known_files: &KnownFiles, root: &Path) -> Option<PathBuf> {
+    let path = known_files.get_path(file_id).ok()?;
+    Some(root.join(path).with_extension("snapshots"))
+}
+
+pub async fn list_handler(
+    file_id: usize,
+    known_files: KnownFiles,
+    root: PathBuf,
+) -> Result<impl Reply, Rejection> {
+    let dir = snapshot_dir(file_id, &known_files, &root).ok_or_else(reject::not_found)?;
+
+    let iterations = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.has_extension("json"))
+        .filter_map(|path| {
+            path.file_stem()?
+                .to_str()?
+                .strip_prefix("iteration-")?
+                .parse()
+                .ok()
+        })
+        .sorted()
+        .collect();
+
+    Ok(json(&SnapshotsResponse { iterations }))
+}
+
+pub async fn body_handler(
+    file_id: usize,
+    iteration: usize,
+    known_files: KnownFiles,
+    root: PathBuf,
+) -> Result<impl Reply, Rejection> {
+    let dir = snapshot_dir(file_id, &known_files, &root).ok_or_else(reject::not_found)?;
+    let body = std::fs::read_to_string(dir.join(format!("iteration-{iteration}.json")))
+        .map_err(|_| reject::not_found())?;
+
+    Ok(with_header(body, CONTENT_TYPE, "application/json"))
+}
@@ -7,7 +7,7 @@ mod webfiles;
 use anyhow::anyhow;
 use itertools::Itertools;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::HashMap,
     convert::Infallible,
     ffi::OsStr,
     fs,
@@ -16,7 +16,7 @@ use std::{
     time::SystemTime,
 };
 
-use handlers::{available, download, ws};
+use handlers::{available, download, snapshot, ws};
 use walkdir::{DirEntry, WalkDir};
 use warp::Filter;
 
@@ -58,12 +58,12 @@ impl KnownFiles {
         let mut counter = self.counter.write().map_err(|e| anyhow!("{e}"))?;
         let mut paths = self.paths.write().map_err(|e| anyhow!("{e}"))?;
 
-        // find all csv paths under `&self.root`
+        // find all csv and jsonl paths under `&self.root`
         let csv_paths: Vec<(PathBuf, SystemTime)> = WalkDir::new(&*self.root)
             .into_iter()
             .flatten()
             .filter(|entry| entry.file_type().is_file())
-            .filter(|entry| entry.has_extension("csv"))
+            .filter(|entry| entry.has_extension("csv") || entry.has_extension("jsonl"))
             .filter(|entry| {
                 !self
                     .excluded
@@ -136,12 +136,27 @@ async fn main() {
         .and(with_root(args.clone()))
         .and_then(download::body_handler);
 
+    let snapshots = warp::path!("snapshots" / usize)
+        .and(with_known_files(known_files.clone()))
+        .and(with_root(args.clone()))
+        .and_then(snapshot::list_handler);
+
+    let snapshot = warp::path!("snapshot" / usize / usize)
+        .and(with_known_files(known_files.clone()))
+        .and(with_root(args.clone()))
+        .and_then(snapshot::body_handler);
+
     let ws_route = warp::path!("ws" / usize)
         .and(warp::ws())
         .and(with_known_files(known_files.clone()))
         .and_then(ws::handler);
 
-    let routes = available.or(download_headers).or(download).or(ws_route);
+    let routes = available
+        .or(download_headers)
+        .or(download)
+        .or(snapshots)
+        .or(snapshot)
+        .or(ws_route);
 
     #[cfg(not(debug_assertions))]
     {
@@ -1,5 +1,6 @@
 use futures::SinkExt;
 use notify::{Config, RecursiveMode, Watcher};
+use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::fs::File;
 use std::io::{Read, Seek, SeekFrom};
@@ -8,19 +9,103 @@ use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use futures::channel::mpsc::{channel, Receiver, Sender};
+
+use egg_stats::{read, Phase, Record, Value};
+
+use crate::HasExtension;
 use notify::{Event, RecommendedWatcher};
 
 #[derive(serde::Deserialize, serde::Serialize, Debug)]
 pub struct Row {
     id: String,
-    iteration: u64,
+    iteration: usize,
     rule_name: String,
     rule: String,
-    when: String,
+    when: Phase,
     name: String,
     value: String,
 }
 
+impl From<Record> for Row {
+    fn from(record: Record) -> Self {
+        Row {
+            id: record.id,
+            iteration: record.iteration,
+            rule_name: record.rule_name,
+            rule: record.rule,
+            when: record.phase,
+            name: record.recorder,
+            value: match record.value {
+                Value::Str(s) => s,
+                value => serde_json::to_string(&value).unwrap_or_default(),
+            },
+        }
+    }
+}
+
+/// Parses every row of a log that can be parsed, `.jsonl` logs if `json` is
+/// set and `.csv` logs otherwise.
+pub fn parse_records(contents: &[u8], json: bool) -> Vec<Record> {
+    if json {
+        read::json_lines_records(contents).flatten().collect()
+    } else {
+        read::csv_records(contents).flatten().collect()
+    }
+}
+
+/// The length of the records at the start of `contents` that have been written
+/// in full, leaving out a trailing record that is still being written.
+fn complete_len(contents: &[u8], json: bool) -> usize {
+    if json {
+        return contents
+            .iter()
+            .rposition(|b| *b == b'\n')
+            .map_or(0, |i| i + 1);
+    }
+
+    // csv records can span several lines within quotes
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(contents);
+    let mut record = csv::ByteRecord::new();
+    let mut complete = 0;
+    while let Ok(true) = reader.read_byte_record(&mut record) {
+        let end = reader.position().byte() as usize;
+        let raw = &contents[complete..end];
+        // the reader ends a record at the end of the input, even within quotes
+        let quotes = raw.iter().filter(|b| **b == b'"').count();
+        if !raw.ends_with(b"\n") || quotes % 2 == 1 {
+            break;
+        }
+        complete = end;
+    }
+    complete
+}
+
+/// The value type of every `name` in a log.
+pub fn value_types<'a>(records: impl Iterator<Item = &'a Record>) -> HashMap<String, String> {
+    let mut types = HashMap::new();
+    for record in records {
+        let typ = record.value.type_name().to_string();
+        merge_type(&mut types, record.recorder.clone(), typ);
+    }
+    types
+}
+
+/// Records that `name` holds values of type `typ`. Ints widen to floats, and
+/// anything else that disagrees is treated as a string.
+fn merge_type(types: &mut HashMap<String, String>, name: String, typ: String) {
+    let merged = match types.get(&name).map(String::as_str) {
+        None => typ,
+        Some(old) if old == typ => typ,
+        Some("int") if typ == "float" => typ,
+        Some("float") if typ == "int" => "float".to_string(),
+        Some(_) => "string".to_string(),
+    };
+    types.insert(name, merged);
+}
+
 #[derive(serde::Serialize, Debug)]
 pub enum RowResponse {
     Located { path: PathBuf, row: Row, order: u64 },
@@ -91,16 +176,17 @@ async fn parse_event(
                 return Ok(());
             }
 
-            for p in event
-                .paths
-                .iter()
-                .filter(|path| path.extension().and_then(OsStr::to_str) == Some("csv"))
-            {
+            for p in event.paths.iter().filter(|path| {
+                matches!(
+                    path.extension().and_then(OsStr::to_str),
+                    Some("csv") | Some("jsonl")
+                )
+            }) {
                 let mut f = File::open(p)?;
                 let filesize = f.metadata()?.size();
 
-                let seek_pos = match (*old_filesize).cmp(&filesize) {
-                    std::cmp::Ordering::Less => SeekFrom::Start(*old_filesize),
+                let start = match (*old_filesize).cmp(&filesize) {
+                    std::cmp::Ordering::Less => *old_filesize,
                     std::cmp::Ordering::Equal => {
                         // file size hasn't changed so we don't have to do anythhing
                         continue;
@@ -110,25 +196,28 @@ async fn parse_event(
                         sender
                             .send(RowResponse::Restart { path: p.clone() })
                             .await?;
-                        SeekFrom::Start(0)
+                        0
                     }
                 };
 
-                f.seek(seek_pos)?;
+                f.seek(SeekFrom::Start(start))?;
 
                 let mut new_contents = Vec::new();
                 f.read_to_end(&mut new_contents)?;
 
-                let mut rdr = csv::ReaderBuilder::new()
-                    .has_headers(false)
-                    .delimiter(b',')
-                    .from_reader(&new_contents[..]);
-                for row in rdr.deserialize::<Row>().flatten() {
+                let json = p.has_extension("jsonl");
+                // leave a trailing partial record for the next event
+                new_contents.truncate(complete_len(&new_contents, json));
+                let rows = parse_records(&new_contents, json)
+                    .into_iter()
+                    .map(Row::from);
+
+                for row in rows {
                     sender.send(row.locate(p.clone()).order(*count)).await?;
                     *count += 1;
                 }
 
-                *old_filesize = filesize;
+                *old_filesize = start + new_contents.len() as u64;
             }
         }
         Err(e) => eprintln!("watch error: {:?}", e),
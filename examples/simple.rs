@@ -1,5 +1,5 @@
 use egg::*;
-use egg_stats::{recorders, LoggingScheduler};
+use egg_stats::{impl_recorder, recorders, IterationArgs, LoggingScheduler};
 use std::path::Path;
 use std::{fs::OpenOptions, time::Instant};
 
@@ -14,6 +14,20 @@ define_language! {
     }
 }
 
+impl_recorder! {
+    /// how many e-nodes each iteration added
+    struct ENodesDelta {
+        previous: usize = 0,
+    };
+    identifier => "enodes_delta",
+    iteration_end => |d: &mut Self, x: IterationArgs<_, _>| {
+        let size = x.egraph.total_size();
+        let delta = size as i64 - d.previous as i64;
+        d.previous = size;
+        Some(delta)
+    }
+}
+
 fn make_rules() -> Vec<Rewrite<SimpleLanguage, ()>> {
     vec![
         rewrite!("commute-add"; "(+ ?a ?b)" => "(+ ?b ?a)"),
@@ -43,27 +57,54 @@ fn simplify_with(
     let mut egraph = EGraph::new(());
     let root = egraph.add_expr(&expr);
 
+    // only re-extract the best program when the e-graph has changed
+    let best_program = recorders::BestProgram::new_with(|| AstSize, root)
+        .with_extraction(recorders::Extraction::OnChange);
+    let overhead = best_program.overhead();
+
+    let scheduler = LoggingScheduler::from(scheduler)
+        .with_out_file(
+            OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(path.as_ref())
+                .unwrap(),
+        )
+        .with_logging_enabled(true)
+        .with_overhead_measured(true)
+        .with_recorder(recorders::Timestamp::new(Instant::now()))
+        .with_recorder(recorders::NumberENodes)
+        .with_recorder(recorders::NumberEClasses)
+        .with_recorder(recorders::EClassSizes)
+        .with_recorder(recorders::OperatorCounts)
+        .with_recorder(recorders::Substitutions)
+        .with_recorder(recorders::Applications)
+        .with_recorder(ENodesDelta::default())
+        .with_recorder(best_program)
+        .with_recorder(overhead)
+        .with_recorder(recorders::BestExpr::new_with(|| AstSize, root))
+        // dump the whole e-graph every 5 iterations, where egg-viz can find it
+        .with_recorder(
+            recorders::Snapshot::beside(path.as_ref())
+                .every(5)
+                .with_roots([root]),
+        );
+    let handle = scheduler.handle();
+
+    // record what the run starts from
+    let rules = make_rules();
+    handle.start(&egraph, &rules);
+
     // simplify the expression using a Runner, which creates an e-graph with
     // the given expression and runs the given rules over it
-    Runner::default()
-        .with_scheduler(
-            LoggingScheduler::from(scheduler)
-                .with_out_file(
-                    OpenOptions::new()
-                        .write(true)
-                        .create(true)
-                        .truncate(true)
-                        .open(path.as_ref())
-                        .unwrap(),
-                )
-                .with_logging_enabled(true)
-                .with_recorder(recorders::Timestamp::new(Instant::now()))
-                .with_recorder(recorders::NumberENodes)
-                .with_recorder(recorders::NumberEClasses)
-                .with_recorder(recorders::BestProgram::new_with(|| AstSize, root)),
-        )
+    let runner = Runner::default()
+        .with_scheduler(scheduler)
         .with_egraph(egraph)
-        .run(&make_rules());
+        .run(&rules);
+
+    // record the end of the final iteration and why the run stopped
+    handle.finish(&runner);
 
     println!("Wrote {:?}", path.as_ref());
 }
@@ -0,0 +1,270 @@
+//! Checking runs against a baseline, to catch regressions in a rewrite system.
+//!
+//! A metric is either something a run recorded about itself when it started or
+//! finished, such as `final_enodes`, `iterations` or `stop_reason`, or the last
+//! value a recorder recorded at the end of an iteration, such as `cost`.
+//!
+//! ```no_run
+//! # fn main() -> egg_stats::Result<()> {
+//! use egg_stats::{baseline::Checks, read};
+//!
+//! let report = Checks::new()
+//!     .within("final_enodes", 0.05)
+//!     .not_above("cost")
+//!     .not_above("iterations")
+//!     .compare(&read::read("baseline.csv")?, &read::read("run.csv")?);
+//! assert!(report.passed(), "{report}");
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+
+use crate::{
+    read::Log,
+    table::{percent, write_table},
+    Value,
+};
+
+/// How a metric of a run may differ from the baseline's.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Bound {
+    /// Within this fraction of the baseline, in either direction.
+    Within(f64),
+    /// No larger than the baseline.
+    NotAbove,
+    /// No smaller than the baseline.
+    NotBelow,
+    /// Equal to the baseline.
+    Same,
+}
+
+impl fmt::Display for Bound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bound::Within(tolerance) => write!(f, "within {}%", tolerance * 100.0),
+            Bound::NotAbove => write!(f, "not above"),
+            Bound::NotBelow => write!(f, "not below"),
+            Bound::Same => write!(f, "same"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Check {
+    pub metric: String,
+    pub bound: Bound,
+}
+
+/// A list of [`Check`]s to compare runs with.
+#[derive(Clone, Debug, Default)]
+pub struct Checks {
+    checks: Vec<Check>,
+}
+
+impl Checks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The final number of e-nodes stays within 5%, the best cost (of either
+    /// [`BestProgram`](crate::recorders::BestProgram) or
+    /// [`BestExpr`](crate::recorders::BestExpr)) and the number of iterations
+    /// don't go up, and the run stops for the same reason.
+    pub fn defaults() -> Self {
+        Self::new()
+            .within("final_enodes", 0.05)
+            .not_above("cost")
+            .not_above("best_program/cost")
+            .not_above("iterations")
+            .same("stop_reason")
+    }
+
+    pub fn with_check(mut self, check: Check) -> Self {
+        self.checks.push(check);
+        self
+    }
+
+    pub fn within(self, metric: impl Into<String>, tolerance: f64) -> Self {
+        self.with_bound(metric, Bound::Within(tolerance))
+    }
+
+    pub fn not_above(self, metric: impl Into<String>) -> Self {
+        self.with_bound(metric, Bound::NotAbove)
+    }
+
+    pub fn not_below(self, metric: impl Into<String>) -> Self {
+        self.with_bound(metric, Bound::NotBelow)
+    }
+
+    pub fn same(self, metric: impl Into<String>) -> Self {
+        self.with_bound(metric, Bound::Same)
+    }
+
+    fn with_bound(self, metric: impl Into<String>, bound: Bound) -> Self {
+        self.with_check(Check {
+            metric: metric.into(),
+            bound,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.checks.is_empty()
+    }
+
+    /// Checks every run of `baseline` against the run with the same id in
+    /// `run`. When both logs hold a single run, those are compared whatever
+    /// their ids. Runs missing from `run` fail every check; runs only in `run`
+    /// aren't checked.
+    pub fn compare(&self, baseline: &Log, run: &Log) -> Report {
+        let mut comparisons = vec![];
+        for (id, old, new) in baseline.pair_runs(run) {
+            if old.is_empty() {
+                continue;
+            }
+            for check in &self.checks {
+                comparisons.push(Comparison::new(
+                    &id,
+                    check.clone(),
+                    metric(&old, &check.metric),
+                    metric(&new, &check.metric),
+                ));
+            }
+        }
+        Report { comparisons }
+    }
+}
+
+/// The value of `name` in a log holding a single run.
+fn metric(run: &Log, name: &str) -> Option<Value> {
+    run.summaries()
+        .first()
+        .and_then(|summary| summary.get(name).cloned())
+        .or_else(|| run.last(name).cloned())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Passed,
+    Failed,
+    /// Neither run recorded the metric.
+    NotRecorded,
+    /// The values differ, but can't be ordered, such as lists or strings
+    /// checked with [`Bound::NotAbove`] or [`Bound::NotBelow`]. This doesn't
+    /// fail the check, but shows up in the report.
+    NotComparable,
+}
+
+/// One check of one run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comparison {
+    pub run: String,
+    pub check: Check,
+    pub baseline: Option<Value>,
+    pub current: Option<Value>,
+    pub outcome: Outcome,
+}
+
+impl Comparison {
+    fn new(run: &str, check: Check, baseline: Option<Value>, current: Option<Value>) -> Self {
+        let outcome = match (&baseline, &current) {
+            (None, None) => Outcome::NotRecorded,
+            (Some(old), Some(new)) => outcome(check.bound, old, new),
+            _ => Outcome::Failed,
+        };
+        Comparison {
+            run: run.to_string(),
+            check,
+            baseline,
+            current,
+            outcome,
+        }
+    }
+
+    /// The relative change from the baseline, if both values are numbers.
+    pub fn change(&self) -> Option<f64> {
+        self.current.as_ref()?.change_from(self.baseline.as_ref()?)
+    }
+}
+
+fn outcome(bound: Bound, old: &Value, new: &Value) -> Outcome {
+    let holds = match (bound, old.as_f64(), new.as_f64()) {
+        (Bound::Within(tolerance), Some(old), Some(new)) => {
+            (new - old).abs() <= tolerance * old.abs()
+        }
+        (Bound::NotAbove, Some(old), Some(new)) => new <= old,
+        (Bound::NotBelow, Some(old), Some(new)) => new >= old,
+        (Bound::Same, Some(old), Some(new)) => new == old,
+        _ if new == old => true,
+        (Bound::Within(_) | Bound::Same, _, _) => false,
+        (Bound::NotAbove | Bound::NotBelow, _, _) => return Outcome::NotComparable,
+    };
+    if holds {
+        Outcome::Passed
+    } else {
+        Outcome::Failed
+    }
+}
+
+/// The outcome of every check. Its `Display` implementation prints a table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    pub comparisons: Vec<Comparison>,
+}
+
+impl Report {
+    /// Whether no check failed.
+    pub fn passed(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &Comparison> {
+        self.comparisons
+            .iter()
+            .filter(|comparison| comparison.outcome == Outcome::Failed)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shown = |value: &Option<Value>| match value {
+            Some(value) => value.to_string(),
+            None => "-".to_string(),
+        };
+        let mut rows = vec![[
+            "run".to_string(),
+            "metric".to_string(),
+            "baseline".to_string(),
+            "current".to_string(),
+            "change".to_string(),
+            "check".to_string(),
+            "result".to_string(),
+        ]];
+        for comparison in &self.comparisons {
+            rows.push([
+                comparison.run.clone(),
+                comparison.check.metric.clone(),
+                shown(&comparison.baseline),
+                shown(&comparison.current),
+                percent(comparison.change()),
+                comparison.check.bound.to_string(),
+                match comparison.outcome {
+                    Outcome::Passed => "ok",
+                    Outcome::Failed => "FAILED",
+                    Outcome::NotRecorded => "not recorded",
+                    Outcome::NotComparable => "not comparable",
+                }
+                .to_string(),
+            ]);
+        }
+
+        write_table(f, "", &rows)?;
+
+        let failures = self.failures().count();
+        if failures == 0 {
+            write!(f, "all {} checks passed", self.comparisons.len())
+        } else {
+            write!(f, "{failures} of {} checks failed", self.comparisons.len())
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::{path::Path, process::ExitCode};
+
+use egg_stats::{baseline::Checks, diff, read};
+
+const USAGE: &str = "\
+usage: egg-stats check <baseline> <run> [checks]
+       egg-stats diff <a> <b> [--recorder <name>]... [--ignore <name>]...
+
+Both commands take two logs, or two directories of logs, and match their runs
+by id. Runs without an id are named after their file.
+
+check: Checks every run of the baseline against the run with the same id, and
+exits with status 1 if any check fails. Without any checks, the final number of
+e-nodes must stay within 5%, the best cost and the number of iterations must
+not go up, and the run must stop for the same reason.
+
+    --within <metric>=<percent>    stays within <percent>% of the baseline
+    --not-above <metric>           is no larger than the baseline
+    --not-below <metric>           is no smaller than the baseline
+    --same <metric>                is equal to the baseline
+
+diff: Reports how the final values, the values at the end of every iteration
+and the per-rule counts of every pair of runs differ, and the first iteration
+at the end of which they differed.
+
+    --recorder <name>              only compare this recorder
+    --ignore <name>                don't compare this recorder";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("check") => check(&args[1..]),
+        Some("diff") => compare(&args[1..]),
+        Some("-h" | "--help") => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        _ => Err("expected a command".to_string()),
+    };
+    match result {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("error: {error}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn read(path: &str) -> Result<read::Log, String> {
+    let log = if Path::new(path).is_dir() {
+        read::read_dir(path)
+    } else {
+        read::read(path)
+    };
+    log.map_err(|e| format!("{path}: {e}"))
+}
+
+fn check(args: &[String]) -> Result<ExitCode, String> {
+    let mut paths = vec![];
+    let mut checks = Checks::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut metric = || args.next().ok_or_else(|| format!("`{arg}` needs a metric"));
+        checks = match arg.as_str() {
+            "--within" => {
+                let within = metric()?;
+                let (metric, percent) = within
+                    .split_once('=')
+                    .and_then(|(metric, percent)| Some((metric, percent.parse::<f64>().ok()?)))
+                    .ok_or_else(|| format!("expected `<metric>=<percent>`, got `{within}`"))?;
+                checks.within(metric, percent / 100.0)
+            }
+            "--not-above" => checks.not_above(metric()?),
+            "--not-below" => checks.not_below(metric()?),
+            "--same" => checks.same(metric()?),
+            flag if flag.starts_with('-') => return Err(format!("unknown option `{flag}`")),
+            path => {
+                paths.push(path);
+                checks
+            }
+        };
+    }
+    let [baseline, run] = paths[..] else {
+        return Err("expected a baseline and a run".to_string());
+    };
+    if checks.is_empty() {
+        checks = Checks::defaults();
+    }
+
+    let report = checks.compare(&read(baseline)?, &read(run)?);
+    println!("{report}");
+    Ok(if report.passed() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+fn compare(args: &[String]) -> Result<ExitCode, String> {
+    let mut paths = vec![];
+    let mut only = vec![];
+    let mut ignored = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut name = || {
+            args.next()
+                .ok_or_else(|| format!("`{arg}` needs a recorder"))
+        };
+        match arg.as_str() {
+            "--recorder" => only.push(name()?),
+            "--ignore" => ignored.push(name()?),
+            flag if flag.starts_with('-') => return Err(format!("unknown option `{flag}`")),
+            path => paths.push(path),
+        }
+    }
+    let [a, b] = paths[..] else {
+        return Err("expected two logs".to_string());
+    };
+
+    // `<recorder>/<name>` rows belong to `<recorder>`
+    let named = |names: &[&String], recorder: &str| {
+        let recorder = recorder.split('/').next().unwrap_or(recorder);
+        names.iter().any(|name| *name == recorder)
+    };
+    let read = |path| {
+        let mut log = read(path)?;
+        log.records.retain(|record| {
+            (only.is_empty() || named(&only, &record.recorder))
+                && !named(&ignored, &record.recorder)
+        });
+        Ok::<_, String>(log)
+    };
+
+    println!("a: {a}\nb: {b}\n");
+    print!("{}", diff::diff(&read(a)?, &read(b)?));
+    Ok(ExitCode::SUCCESS)
+}
@@ -1,4 +1,6 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, time::Duration};
+
+use crate::{Error, Phase, Value};
 
 pub trait Recorder<L, N>
 where
@@ -7,6 +9,94 @@ where
 {
     fn identifier(&self) -> Cow<'static, str>;
 
+    #[allow(unused_variables)]
+    fn record_before_search<'r>(
+        &mut self,
+        iteration: usize,
+        egraph: &'r egg::EGraph<L, N>,
+        rewrite: &'r egg::Rewrite<L, N>,
+    ) -> Option<Value> {
+        None
+    }
+
+    #[allow(unused_variables)]
+    fn record_after_search<'r>(
+        &mut self,
+        iteration: usize,
+        egraph: &'r egg::EGraph<L, N>,
+        rewrite: &'r egg::Rewrite<L, N>,
+        matches: &[egg::SearchMatches<L>],
+    ) -> Option<Value> {
+        None
+    }
+
+    #[allow(unused_variables)]
+    fn record_before_rewrite(
+        &mut self,
+        iteration: usize,
+        egraph: &mut egg::EGraph<L, N>,
+        rewrite: &egg::Rewrite<L, N>,
+        matches: &[egg::SearchMatches<L>],
+    ) -> Option<Value> {
+        None
+    }
+
+    #[allow(unused_variables)]
+    fn record_after_rewrite(
+        &mut self,
+        iteration: usize,
+        egraph: &mut egg::EGraph<L, N>,
+        rewrite: &egg::Rewrite<L, N>,
+        n_matches: usize,
+    ) -> Option<Value> {
+        None
+    }
+
+    #[allow(unused_variables)]
+    fn record_iteration_start(
+        &mut self,
+        iteration: usize,
+        egraph: &egg::EGraph<L, N>,
+    ) -> Option<Value> {
+        None
+    }
+
+    #[allow(unused_variables)]
+    fn record_iteration_end(
+        &mut self,
+        iteration: usize,
+        egraph: &egg::EGraph<L, N>,
+    ) -> Option<Value> {
+        None
+    }
+
+    /// Told how long the wrapped scheduler took to search for a rule or to
+    /// apply it, right before the `after_search` or `after_rewrite` hooks run.
+    /// `phase` is the phase of those hooks. The time doesn't include any
+    /// recorder's hooks, nor writing what they return.
+    #[allow(unused_variables)]
+    fn inner_time(&mut self, phase: Phase, elapsed: Duration) {}
+
+    /// An error the recorder ran into during its last hook. The scheduler
+    /// checks after every hook, and handles it like a failed write, according
+    /// to its [`OnError`](crate::OnError) policy.
+    fn take_error(&mut self) -> Option<Error> {
+        None
+    }
+}
+
+/// The original, string-valued form of [`Recorder`].
+///
+/// Recorders written against the older API keep working by implementing this
+/// trait instead; [`LoggingScheduler::with_recorder`](crate::LoggingScheduler::with_recorder)
+/// accepts both.
+pub trait StringRecorder<L, N>
+where
+    L: egg::Language,
+    N: egg::Analysis<L>,
+{
+    fn identifier(&self) -> Cow<'static, str>;
+
     #[allow(unused_variables)]
     fn record_before_search<'r>(
         &self,
@@ -49,6 +139,148 @@ where
     ) -> Option<String> {
         None
     }
+
+    #[allow(unused_variables)]
+    fn record_iteration_start(
+        &self,
+        iteration: usize,
+        egraph: &egg::EGraph<L, N>,
+    ) -> Option<String> {
+        None
+    }
+
+    #[allow(unused_variables)]
+    fn record_iteration_end(&self, iteration: usize, egraph: &egg::EGraph<L, N>) -> Option<String> {
+        None
+    }
+}
+
+/// Wraps a [`StringRecorder`] so that it can be used as a [`Recorder`].
+pub struct StringAdapter<R>(pub R);
+
+impl<L, N, R> Recorder<L, N> for StringAdapter<R>
+where
+    L: egg::Language,
+    N: egg::Analysis<L>,
+    R: StringRecorder<L, N>,
+{
+    fn identifier(&self) -> Cow<'static, str> {
+        self.0.identifier()
+    }
+
+    fn record_before_search<'r>(
+        &mut self,
+        iteration: usize,
+        egraph: &'r egg::EGraph<L, N>,
+        rewrite: &'r egg::Rewrite<L, N>,
+    ) -> Option<Value> {
+        self.0
+            .record_before_search(iteration, egraph, rewrite)
+            .map(Value::Str)
+    }
+
+    fn record_after_search<'r>(
+        &mut self,
+        iteration: usize,
+        egraph: &'r egg::EGraph<L, N>,
+        rewrite: &'r egg::Rewrite<L, N>,
+        matches: &[egg::SearchMatches<L>],
+    ) -> Option<Value> {
+        self.0
+            .record_after_search(iteration, egraph, rewrite, matches)
+            .map(Value::Str)
+    }
+
+    fn record_before_rewrite(
+        &mut self,
+        iteration: usize,
+        egraph: &mut egg::EGraph<L, N>,
+        rewrite: &egg::Rewrite<L, N>,
+        matches: &[egg::SearchMatches<L>],
+    ) -> Option<Value> {
+        self.0
+            .record_before_rewrite(iteration, egraph, rewrite, matches)
+            .map(Value::Str)
+    }
+
+    fn record_after_rewrite(
+        &mut self,
+        iteration: usize,
+        egraph: &mut egg::EGraph<L, N>,
+        rewrite: &egg::Rewrite<L, N>,
+        n_matches: usize,
+    ) -> Option<Value> {
+        self.0
+            .record_after_rewrite(iteration, egraph, rewrite, n_matches)
+            .map(Value::Str)
+    }
+
+    fn record_iteration_start(
+        &mut self,
+        iteration: usize,
+        egraph: &egg::EGraph<L, N>,
+    ) -> Option<Value> {
+        self.0
+            .record_iteration_start(iteration, egraph)
+            .map(Value::Str)
+    }
+
+    fn record_iteration_end(
+        &mut self,
+        iteration: usize,
+        egraph: &egg::EGraph<L, N>,
+    ) -> Option<Value> {
+        self.0
+            .record_iteration_end(iteration, egraph)
+            .map(Value::Str)
+    }
+}
+
+#[doc(hidden)]
+pub struct Typed;
+
+#[doc(hidden)]
+pub struct Stringly;
+
+/// Anything that can be turned into a [`Recorder`]: either a `Recorder` itself
+/// or a [`StringRecorder`].
+///
+/// The `M` parameter only exists to keep the two blanket implementations apart
+/// and is always inferred.
+pub trait IntoRecorder<L, N, M>
+where
+    L: egg::Language,
+    N: egg::Analysis<L>,
+{
+    type Recorder: Recorder<L, N>;
+
+    fn into_recorder(self) -> Self::Recorder;
+}
+
+impl<L, N, R> IntoRecorder<L, N, Typed> for R
+where
+    L: egg::Language,
+    N: egg::Analysis<L>,
+    R: Recorder<L, N>,
+{
+    type Recorder = R;
+
+    fn into_recorder(self) -> R {
+        self
+    }
+}
+
+impl<L, N, R> IntoRecorder<L, N, Stringly> for R
+where
+    L: egg::Language,
+    N: egg::Analysis<L>,
+    R: StringRecorder<L, N>,
+{
+    type Recorder = StringAdapter<R>;
+
+    fn into_recorder(self) -> StringAdapter<R> {
+        StringAdapter(self)
+    }
 }
 
 pub struct BeforeSearchArgs<'a, L, N>
@@ -83,6 +315,15 @@ where
     pub matches: &'a [egg::SearchMatches<'a, L>],
 }
 
+pub struct IterationArgs<'a, L, N>
+where
+    L: egg::Language,
+    N: egg::Analysis<L>,
+{
+    pub iteration: usize,
+    pub egraph: &'a egg::EGraph<L, N>,
+}
+
 pub struct AfterRewriteArgs<'a, L, N>
 where
     L: egg::Language,
@@ -97,7 +338,7 @@ where
 #[macro_export]
 macro_rules! impl_recorder {
     ($type_name:ident; $($name:ident => $val:expr),*) => {
-        impl<L, N> Recorder<L, N> for $type_name
+        impl<L, N> $crate::Recorder<L, N> for $type_name
         where
             L: egg::Language,
             N: egg::Analysis<L>,
@@ -106,80 +347,129 @@ macro_rules! impl_recorder {
         }
     };
 
-    ($vis:vis struct $type_name:ident; $($name:ident => $val:expr),*) => {
+    ($(#[$meta:meta])* $vis:vis struct $type_name:ident; $($name:ident => $val:expr),*) => {
+        $(#[$meta])*
         $vis struct $type_name;
         impl_recorder!($type_name; $($name => $val),*);
     };
 
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $type_name:ident {
+            $($field_vis:vis $field:ident: $field_ty:ty = $init:expr),* $(,)?
+        };
+        $($name:ident => $val:expr),*
+    ) => {
+        $(#[$meta])*
+        $vis struct $type_name {
+            $($field_vis $field: $field_ty),*
+        }
+
+        impl Default for $type_name {
+            fn default() -> Self {
+                $type_name {
+                    $($field: $init),*
+                }
+            }
+        }
+
+        impl_recorder!($type_name; $($name => $val),*);
+    };
+
     (impl identifier, $name:literal) => {
-        fn identifier(&self) -> Cow<'static, str> {
+        fn identifier(&self) -> ::std::borrow::Cow<'static, str> {
             $name.into()
         }
     };
 
     (impl before_search, $br:expr) => {
         fn record_before_search<'r>(
-            &self,
+            &mut self,
             iteration: usize,
             egraph: &'r egg::EGraph<L, N>,
             rewrite: &'r egg::Rewrite<L, N>,
-        ) -> Option<String> {
+        ) -> Option<$crate::Value> {
             $br(self, $crate::BeforeSearchArgs {
                 iteration,
                 egraph,
                 rewrite,
             })
+            .map(::std::convert::Into::into)
         }
     };
 
     (impl after_search, $br:expr) => {
         fn record_after_search<'r>(
-            &self,
+            &mut self,
             iteration: usize,
             egraph: &'r egg::EGraph<L, N>,
             rewrite: &'r egg::Rewrite<L, N>,
             matches: &[egg::SearchMatches<L>],
-        ) -> Option<String> {
+        ) -> Option<$crate::Value> {
             $br(self, $crate::AfterSearchArgs {
                 iteration,
                 egraph,
                 rewrite,
                 matches,
             })
+            .map(::std::convert::Into::into)
         }
     };
 
     (impl before_rewrite, $br:expr) => {
         fn record_before_rewrite(
-            &self,
+            &mut self,
             iteration: usize,
             egraph: &mut egg::EGraph<L, N>,
             rewrite: &egg::Rewrite<L, N>,
             matches: &[egg::SearchMatches<L>],
-        ) -> Option<String> {
+        ) -> Option<$crate::Value> {
             $br(self, $crate::BeforeRewriteArgs {
                 iteration,
                 egraph,
                 rewrite,
                 matches,
             })
+            .map(::std::convert::Into::into)
         }
     };
 
     (impl after_rewrite, $br:expr) => {
         fn record_after_rewrite(
-            &self,
+            &mut self,
             iteration: usize,
             egraph: &mut egg::EGraph<L, N>,
             rewrite: &egg::Rewrite<L, N>,
             n_matches: usize,
-        ) -> Option<String> {
+        ) -> Option<$crate::Value> {
             $br(self, $crate::AfterRewriteArgs {
                 iteration,
                 egraph,
                 rewrite,
                 n_matches,
             })
+            .map(::std::convert::Into::into)
+        }
+    };
+    (impl iteration_start, $br:expr) => {
+        fn record_iteration_start(
+            &mut self,
+            iteration: usize,
+            egraph: &egg::EGraph<L, N>,
+        ) -> Option<$crate::Value> {
+            $br(self, $crate::IterationArgs { iteration, egraph })
+                .map(::std::convert::Into::into)
+        }
+    };
+
+    (impl iteration_end, $br:expr) => {
+        fn record_iteration_end(
+            &mut self,
+            iteration: usize,
+            egraph: &egg::EGraph<L, N>,
+        ) -> Option<$crate::Value> {
+            $br(self, $crate::IterationArgs { iteration, egraph })
+                .map(::std::convert::Into::into)
         }
     };
 }
@@ -0,0 +1,352 @@
+//! Comparing two logs run by run, such as the same problem run under two
+//! schedulers.
+//!
+//! Times differ between any two runs, and so do the paths of the files written
+//! next to their logs, such as [`Snapshot`](crate::recorders::Snapshot)s. So
+//! the recorders that record them, and any duration, are never taken as a sign
+//! that two runs diverged; only their final values are compared.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use crate::{
+    read::Log,
+    table::{percent, write_table},
+    Phase, Value,
+};
+
+/// Recorders whose values differ between any two runs: the provided recorders
+/// and overheads that record times, and those that record the paths of files
+/// written next to a log.
+const RUN_DEPENDENT: [&str; 7] = [
+    "time",
+    "rule_search_time",
+    "rule_apply_time",
+    "extraction_time",
+    "recorder_time",
+    "sink_time",
+    "snapshot",
+];
+
+/// A value of one run next to the same value of the other.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Delta {
+    pub name: String,
+    pub a: Option<Value>,
+    pub b: Option<Value>,
+}
+
+impl Delta {
+    pub fn differs(&self) -> bool {
+        self.a != self.b
+    }
+
+    /// The change from `a` to `b`, relative to `a`.
+    pub fn change(&self) -> Option<f64> {
+        self.b.as_ref()?.change_from(self.a.as_ref()?)
+    }
+
+    /// Whether both runs recorded the value, and it differs without being a
+    /// time or a path.
+    fn diverges(&self) -> bool {
+        // `<recorder>/<name>` values belong to `<recorder>`
+        let recorder = self.name.split('/').next().unwrap_or(&self.name);
+        if RUN_DEPENDENT.contains(&recorder) {
+            return false;
+        }
+        let exact = |value: &Option<Value>| {
+            value
+                .as_ref()
+                .is_some_and(|value| !matches!(value, Value::Duration(_)))
+        };
+        self.differs() && exact(&self.a) && exact(&self.b)
+    }
+}
+
+/// The values of a recorder at the end of every iteration of either run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Curve {
+    pub recorder: String,
+    pub points: Vec<(usize, Delta)>,
+}
+
+impl Curve {
+    /// The first iteration that both runs recorded a different value for,
+    /// other than a time or a path.
+    pub fn diverges_at(&self) -> Option<usize> {
+        self.points
+            .iter()
+            .find(|(_, delta)| delta.diverges())
+            .map(|(iteration, _)| *iteration)
+    }
+}
+
+/// The total of a per-rule count, such as
+/// [`Substitutions`](crate::recorders::Substitutions), over a whole run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuleTotal {
+    pub recorder: String,
+    pub rule: String,
+    pub a: i64,
+    pub b: i64,
+}
+
+/// The differences between two runs with the same id.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunDiff {
+    pub run: String,
+    pub in_a: bool,
+    pub in_b: bool,
+    /// What the runs recorded about themselves when they started and finished,
+    /// followed by the last value of every recorder at the end of an
+    /// iteration.
+    pub finals: Vec<Delta>,
+    pub curves: Vec<Curve>,
+    /// Totals of every integer recorder that only records around rules.
+    pub rules: Vec<RuleTotal>,
+}
+
+impl RunDiff {
+    fn new(run: String, a: &Log, b: &Log) -> Self {
+        let summary = |log: &Log| {
+            log.summaries()
+                .into_iter()
+                .next()
+                .map(|summary| summary.values)
+                .unwrap_or_default()
+        };
+        let (summary_a, summary_b) = (summary(a), summary(b));
+        let mut finals: Vec<Delta> = summary_a
+            .keys()
+            .chain(summary_b.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|name| Delta {
+                name: name.clone(),
+                a: summary_a.get(name).cloned(),
+                b: summary_b.get(name).cloned(),
+            })
+            .collect();
+
+        let iteration_recorders = recorders(a, b, |phase| phase == Phase::IterationEnd);
+        let mut curves = vec![];
+        for recorder in &iteration_recorders {
+            if !finals.iter().any(|delta| &delta.name == recorder) {
+                finals.push(Delta {
+                    name: recorder.clone(),
+                    a: a.last(recorder).cloned(),
+                    b: b.last(recorder).cloned(),
+                });
+            }
+
+            let mut points: BTreeMap<usize, Delta> = BTreeMap::new();
+            for (iteration, value) in a.series(recorder) {
+                points.entry(iteration).or_insert_with(|| delta(recorder)).a = Some(value.clone());
+            }
+            for (iteration, value) in b.series(recorder) {
+                points.entry(iteration).or_insert_with(|| delta(recorder)).b = Some(value.clone());
+            }
+            curves.push(Curve {
+                recorder: recorder.clone(),
+                points: points.into_iter().collect(),
+            });
+        }
+
+        let mut rules = vec![];
+        let per_rule = |phase| matches!(phase, Phase::AfterSearch | Phase::AfterRewrite);
+        for recorder in recorders(a, b, per_rule) {
+            if iteration_recorders.contains(&recorder) {
+                continue;
+            }
+            let (totals_a, totals_b) = (rule_totals(a, &recorder), rule_totals(b, &recorder));
+            let (Some(totals_a), Some(totals_b)) = (totals_a, totals_b) else {
+                continue;
+            };
+            let names: BTreeSet<_> = totals_a.keys().chain(totals_b.keys()).collect();
+            for rule in names {
+                rules.push(RuleTotal {
+                    recorder: recorder.clone(),
+                    rule: rule.to_string(),
+                    a: totals_a.get(rule).copied().unwrap_or_default(),
+                    b: totals_b.get(rule).copied().unwrap_or_default(),
+                });
+            }
+        }
+
+        RunDiff {
+            run,
+            in_a: !a.is_empty(),
+            in_b: !b.is_empty(),
+            finals,
+            curves,
+            rules,
+        }
+    }
+
+    /// The first iteration at the end of which the runs recorded different
+    /// values, along with the first value that differed.
+    pub fn diverged_at(&self) -> Option<(usize, &Delta)> {
+        self.curves
+            .iter()
+            .filter_map(|curve| {
+                let iteration = curve.diverges_at()?;
+                let (_, delta) = curve.points.iter().find(|(i, _)| *i == iteration)?;
+                Some((iteration, delta))
+            })
+            .min_by_key(|(iteration, _)| *iteration)
+    }
+}
+
+fn delta(name: &str) -> Delta {
+    Delta {
+        name: name.to_string(),
+        a: None,
+        b: None,
+    }
+}
+
+/// The recorders either log recorded in a phase matching `phase`, in the
+/// order they first appear.
+fn recorders(a: &Log, b: &Log, phase: impl Fn(Phase) -> bool) -> Vec<String> {
+    let mut recorders: Vec<String> = vec![];
+    for record in a.records.iter().chain(&b.records) {
+        if phase(record.phase) && !recorders.contains(&record.recorder) {
+            recorders.push(record.recorder.clone());
+        }
+    }
+    recorders
+}
+
+/// The total of `recorder` for every rule, if all its values are integers.
+fn rule_totals(log: &Log, recorder: &str) -> Option<BTreeMap<String, i64>> {
+    let mut totals = BTreeMap::new();
+    for phase in [Phase::AfterSearch, Phase::AfterRewrite] {
+        for (rule, values) in log.by_rule(recorder, phase) {
+            for (_, value) in values {
+                let Value::Int(n) = value else {
+                    return None;
+                };
+                *totals.entry(rule.to_string()).or_default() += n;
+            }
+        }
+    }
+    Some(totals)
+}
+
+/// Compares the runs of `a` and `b` that have the same id. When both logs hold
+/// a single run, those are compared whatever their ids.
+pub fn diff(a: &Log, b: &Log) -> Diff {
+    Diff {
+        runs: a
+            .pair_runs(b)
+            .into_iter()
+            .map(|(id, a, b)| RunDiff::new(id, &a, &b))
+            .collect(),
+    }
+}
+
+/// The differences between every pair of runs. Its `Display` implementation
+/// prints a report, leaving out values that are the same in both runs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diff {
+    pub runs: Vec<RunDiff>,
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shown = |value: &Option<Value>| match value {
+            Some(value) => value.to_string(),
+            None => "-".to_string(),
+        };
+        let header = |name: &str| [name, "a", "b", "change"].map(|column| column.to_string());
+        let row = |name: String, delta: &Delta| {
+            [
+                name,
+                shown(&delta.a),
+                shown(&delta.b),
+                percent(delta.change()),
+            ]
+        };
+
+        for (i, run) in self.runs.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            match (run.in_a, run.in_b) {
+                (true, false) => writeln!(f, "run {} (only in a)", run.run)?,
+                (false, true) => writeln!(f, "run {} (only in b)", run.run)?,
+                _ => writeln!(f, "run {}", run.run)?,
+            }
+            if !(run.in_a && run.in_b) {
+                continue;
+            }
+
+            match run.diverged_at() {
+                Some((iteration, delta)) => writeln!(
+                    f,
+                    "  diverged in iteration {iteration}: {} is {} in a and {} in b",
+                    delta.name,
+                    shown(&delta.a),
+                    shown(&delta.b),
+                )?,
+                None => writeln!(f, "  never diverged")?,
+            }
+
+            let differing: Vec<_> = run.finals.iter().filter(|delta| delta.differs()).collect();
+            if !differing.is_empty() {
+                writeln!(
+                    f,
+                    "  final values ({} the same):",
+                    run.finals.len() - differing.len()
+                )?;
+                let mut rows = vec![header("name")];
+                rows.extend(
+                    differing
+                        .into_iter()
+                        .map(|delta| row(delta.name.clone(), delta)),
+                );
+                write_table(f, "    ", &rows)?;
+            }
+
+            for curve in &run.curves {
+                if curve.diverges_at().is_none() {
+                    continue;
+                }
+                writeln!(f, "  {} per iteration, where it differs:", curve.recorder)?;
+                let mut rows = vec![header("iteration")];
+                rows.extend(
+                    curve
+                        .points
+                        .iter()
+                        .filter(|(_, delta)| delta.diverges())
+                        .map(|(iteration, delta)| row(iteration.to_string(), delta)),
+                );
+                write_table(f, "    ", &rows)?;
+            }
+
+            let rules: Vec<_> = run
+                .rules
+                .iter()
+                .filter(|total| total.a != total.b)
+                .collect();
+            if !rules.is_empty() {
+                writeln!(f, "  per rule:")?;
+                let mut rows =
+                    vec![["recorder", "rule", "a", "b", "change"].map(|column| column.to_string())];
+                rows.extend(rules.into_iter().map(|total| {
+                    [
+                        total.recorder.clone(),
+                        total.rule.clone(),
+                        total.a.to_string(),
+                        total.b.to_string(),
+                        percent(Value::Int(total.b).change_from(&Value::Int(total.a))),
+                    ]
+                }));
+                write_table(f, "    ", &rows)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use std::fmt;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Csv(csv::Error),
+    Json(serde_json::Error),
+    Disconnected,
+    /// A log being read back isn't in the expected format.
+    Malformed(String),
+    /// A [`Snapshot`](crate::recorders::Snapshot) couldn't be written to this
+    /// path.
+    Snapshot(std::path::PathBuf, Box<Error>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "i/o error: {e}"),
+            Error::Csv(e) => write!(f, "csv error: {e}"),
+            Error::Json(e) => write!(f, "json error: {e}"),
+            Error::Disconnected => write!(f, "the receiving end of a sink has hung up"),
+            Error::Malformed(reason) => write!(f, "malformed log: {reason}"),
+            Error::Snapshot(path, e) => write!(f, "unable to write snapshot {path:?}: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Csv(e) => Some(e),
+            Error::Json(e) => Some(e),
+            Error::Snapshot(_, e) => Some(e),
+            Error::Disconnected | Error::Malformed(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Error::Io(value)
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(value: csv::Error) -> Self {
+        Error::Csv(value)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Error::Json(value)
+    }
+}
+
+/// What a [`LoggingScheduler`](crate::LoggingScheduler) does when writing a record fails.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OnError {
+    /// Panic with the error, aborting the run.
+    #[default]
+    Panic,
+    /// Keep the error and turn logging off for the rest of the run.
+    Disable,
+    /// Keep the error and carry on logging.
+    Collect,
+}
@@ -1,6 +1,29 @@
+pub mod baseline;
 mod datum;
+pub mod diff;
+mod error;
 mod logging_scheduler;
+mod phase;
+pub mod read;
 pub mod recorders;
+mod run_log;
+mod sampling;
+mod sink;
+pub mod sinks;
+pub mod snapshot;
+mod table;
+mod value;
 
-pub use datum::{AfterRewriteArgs, AfterSearchArgs, BeforeRewriteArgs, BeforeSearchArgs, Recorder};
-pub use logging_scheduler::{manual_row, write_headers, LoggingScheduler};
+pub use datum::{
+    AfterRewriteArgs, AfterSearchArgs, BeforeRewriteArgs, BeforeSearchArgs, IntoRecorder,
+    IterationArgs, Recorder, StringAdapter, StringRecorder,
+};
+pub use error::{Error, OnError, Result};
+#[allow(deprecated)]
+pub use logging_scheduler::{manual_row, write_headers};
+pub use logging_scheduler::{LogHandle, LoggingScheduler};
+pub use phase::{Phase, UnknownPhase, FORMAT_VERSION};
+pub use run_log::{Row, RunLog};
+pub use sampling::Sampling;
+pub use sink::{Record, Sink};
+pub use value::Value;
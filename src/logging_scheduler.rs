@@ -1,26 +1,225 @@
-use std::{borrow::Cow, fs::OpenOptions, io::Write, marker::PhantomData, path::Path};
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::HashSet,
+    fs::OpenOptions,
+    io::Write,
+    path::Path,
+    rc::Rc,
+    time::{Duration, Instant, SystemTime},
+};
 
-use crate::Recorder;
+use crate::{
+    sinks::{CsvSink, Overflow, ThreadedSink},
+    Error, IntoRecorder, OnError, Phase, Record, Recorder, Result, Sampling, Sink, Value,
+    FORMAT_VERSION,
+};
 
+/// The identifier of runs that weren't given one.
+pub(crate) const DEFAULT_IDENTIFIER: &str = "default";
+
+/// Like `egg::Runner`, a `LoggingScheduler` stays on the thread it was made
+/// on. To log many runs in parallel, make one scheduler per thread and give
+/// each a [`RunSink`](crate::sinks::RunSink) of the same
+/// [`SharedSink`](crate::sinks::SharedSink).
 pub struct LoggingScheduler<'a, S, L, N> {
-    identifier: String,
-    out_file: Box<dyn Write + 'a>,
     inner_scheduler: S,
+    logger: Rc<RefCell<Logger<'a, L, N>>>,
+}
+
+/// Everything a [`LoggingScheduler`] needs to write records, shared with the
+/// [`LogHandle`]s handed out for it.
+struct Logger<'a, L, N> {
+    identifier: String,
+    sink: Box<dyn Sink + 'a>,
     logging: bool,
-    phantom: PhantomData<(L, N)>,
+    on_error: OnError,
+    errors: Vec<Error>,
+    /// the type name of the wrapped scheduler
+    scheduler: &'static str,
+    /// extra values written with the run header
+    metadata: Vec<(String, Value)>,
+    /// whether the format version marker has been written yet
+    versioned: bool,
+    /// the iteration that was last seen
+    iteration: Option<usize>,
+    sampling: Sampling,
+    /// whether `iteration` is being recorded
+    recording_iteration: bool,
+    /// whether rules have stopped being recorded, see
+    /// [`Sampling::iterations_only_above`]
+    iterations_only: bool,
+    /// the rules recorded during `iteration`
+    recorded_rules: HashSet<egg::Symbol>,
     recorders: Vec<Box<dyn Recorder<L, N> + 'a>>,
+    /// time spent on each recorder during the current iteration, when
+    /// measuring overhead
+    overhead: Option<Vec<Overhead>>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Overhead {
+    /// inside the recorder's hooks
+    record: Duration,
+    /// writing what the recorder returned
+    write: Duration,
+}
+
+/// A handle onto a [`LoggingScheduler`] that stays usable after the scheduler
+/// has been moved into an `egg::Runner`.
+pub struct LogHandle<'a, L, N> {
+    logger: Rc<RefCell<Logger<'a, L, N>>>,
+}
+
+impl<'a, L, N> Clone for LogHandle<'a, L, N> {
+    fn clone(&self) -> Self {
+        LogHandle {
+            logger: Rc::clone(&self.logger),
+        }
+    }
+}
+
+impl<'a, L, N> LogHandle<'a, L, N> {
+    pub fn has_errors(&self) -> bool {
+        !self.logger.borrow().errors.is_empty()
+    }
+
+    pub fn take_errors(&self) -> Vec<Error> {
+        std::mem::take(&mut self.logger.borrow_mut().errors)
+    }
+
+    /// Makes sure that everything recorded so far has been written out.
+    /// [`LogHandle::finish`] flushes too, and so does dropping the scheduler
+    /// and all of its handles, but only this reports errors.
+    pub fn flush(&self) -> Result<()> {
+        self.logger.borrow_mut().sink.flush()
+    }
+
+    /// Writes the `run_start` records describing the run that is about to
+    /// start on `egraph` with `rules`.
+    pub fn start<'r>(
+        &self,
+        egraph: &egg::EGraph<L, N>,
+        rules: impl IntoIterator<Item = &'r egg::Rewrite<L, N>>,
+    ) where
+        L: egg::Language + std::fmt::Display + 'r,
+        N: egg::Analysis<L> + 'r,
+    {
+        let mut logger = self.logger.borrow_mut();
+        if !logger.logging {
+            return;
+        }
+
+        let rule_names: Vec<Value> = rules
+            .into_iter()
+            .map(|rule| Value::display(rule.name))
+            .collect();
+        let start_time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+
+        let mut header: Vec<(Cow<'static, str>, Value)> = vec![
+            ("start_time".into(), start_time.into()),
+            ("rules".into(), rule_names.len().into()),
+            ("rule_names".into(), Value::List(rule_names)),
+            ("initial_enodes".into(), egraph.total_size().into()),
+            ("initial_eclasses".into(), egraph.number_of_classes().into()),
+            ("scheduler".into(), logger.scheduler.into()),
+        ];
+        if let Some(host) = hostname() {
+            header.push(("host".into(), host.into()));
+        }
+        header.extend(
+            logger
+                .metadata
+                .iter()
+                .map(|(name, value)| (name.clone().into(), value.clone())),
+        );
+
+        for (name, value) in header {
+            logger.write(0, None, Phase::RunStart, name, value);
+        }
+    }
+
+    /// Records the end of the last iteration of `runner`, writes the `run_end`
+    /// records saying why and how the run stopped, and flushes the sink.
+    ///
+    /// The scheduler notices that an iteration is over when the next one
+    /// starts, so the final iteration only gets its `iteration_end` records
+    /// once this is called.
+    pub fn finish<IterData>(&self, runner: &egg::Runner<L, N, IterData>)
+    where
+        L: egg::Language + std::fmt::Display,
+        N: egg::Analysis<L>,
+        IterData: egg::IterationData<L, N>,
+    {
+        let mut logger = self.logger.borrow_mut();
+        if !logger.logging {
+            return;
+        }
+
+        if let Some(iteration) = logger.iteration.take() {
+            logger.iteration_end(iteration, &runner.egraph);
+        }
+
+        let report = runner.report();
+        let secs = |s: f64| Value::Duration(Duration::try_from_secs_f64(s).unwrap_or_default());
+        let mut footer: Vec<(&'static str, Value)> = vec![
+            (
+                "stop_reason",
+                stop_reason_str(runner.stop_reason.as_ref()).into(),
+            ),
+            ("iterations", runner.iterations.len().into()),
+            ("final_enodes", runner.egraph.total_size().into()),
+            ("final_eclasses", runner.egraph.number_of_classes().into()),
+            ("rebuilds", report.rebuilds.into()),
+            ("search_time", secs(report.search_time)),
+            ("apply_time", secs(report.apply_time)),
+            ("rebuild_time", secs(report.rebuild_time)),
+            ("total_time", secs(report.total_time)),
+        ];
+        if let Some(last) = runner.iterations.last() {
+            footer.extend([
+                ("last_applied", last.applied.values().sum::<usize>().into()),
+                ("last_search_time", secs(last.search_time)),
+                ("last_apply_time", secs(last.apply_time)),
+                ("last_rebuild_time", secs(last.rebuild_time)),
+            ]);
+        }
+
+        for (name, value) in footer {
+            logger.write(
+                runner.iterations.len(),
+                None,
+                Phase::RunEnd,
+                name.into(),
+                value,
+            );
+        }
+
+        if !logger.logging {
+            return;
+        }
+        if let Err(e) = logger.sink.flush() {
+            logger.fail(e);
+        }
+    }
 }
 
-pub fn write_headers(path: impl AsRef<Path>) {
-    let mut file = OpenOptions::new()
+#[deprecated(note = "use `RunLog::csv`, which writes the headers once")]
+pub fn write_headers(path: impl AsRef<Path>) -> Result<()> {
+    let file = OpenOptions::new()
         .truncate(true)
         .write(true)
         .create(true)
-        .open(path)
-        .unwrap();
-    writeln!(&mut file, "id,iteration,rule_name,rule,when,name,value").unwrap()
+        .open(path)?;
+    CsvSink::new(file).write_headers()
 }
 
+/// `typ` is written as the row's phase as it is, so it needn't be a [`Phase`].
+#[deprecated(note = "use `RunLog::row`")]
+#[allow(clippy::too_many_arguments)]
 pub fn manual_row(
     identifier: impl std::fmt::Display,
     path: impl AsRef<Path>,
@@ -30,99 +229,363 @@ pub fn manual_row(
     typ: &str,
     id: impl std::fmt::Display,
     datum: impl std::fmt::Display,
-) {
-    let mut file = OpenOptions::new().append(true).open(path).unwrap();
-    writeln!(
-        &mut file,
-        "{},{},{},{},{},{},{}",
-        identifier, iteration, name, name_info, typ, id, datum
-    )
-    .unwrap();
+) -> Result<()> {
+    let file = OpenOptions::new().append(true).open(path)?;
+    let mut out = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(file);
+    out.write_record([
+        identifier.to_string(),
+        iteration.to_string(),
+        name.to_string(),
+        name_info.to_string(),
+        typ.to_string(),
+        id.to_string(),
+        datum.to_string(),
+    ])?;
+    out.flush()?;
+    Ok(())
 }
 
 impl<'a, S, L, N> LoggingScheduler<'a, S, L, N> {
     pub fn new(scheduler: S) -> Self {
         LoggingScheduler {
-            identifier: "default".to_string(),
-            out_file: Box::new(std::io::stdout()),
             inner_scheduler: scheduler,
-            logging: false,
-            phantom: PhantomData,
-            recorders: vec![],
+            logger: Rc::new(RefCell::new(Logger {
+                identifier: DEFAULT_IDENTIFIER.to_string(),
+                sink: Box::new(CsvSink::new(std::io::stdout())),
+                logging: false,
+                on_error: OnError::default(),
+                errors: vec![],
+                scheduler: std::any::type_name::<S>(),
+                metadata: vec![],
+                versioned: false,
+                iteration: None,
+                sampling: Sampling::default(),
+                recording_iteration: false,
+                iterations_only: false,
+                recorded_rules: HashSet::new(),
+                recorders: vec![],
+                overhead: None,
+            })),
         }
     }
 
     pub fn with_identifier(mut self, id: impl ToString) -> Self {
-        self.identifier = id.to_string();
+        self.identifier(id);
         self
     }
 
-    pub fn with_out_file<W: Write + 'a>(mut self, out_file: W) -> Self {
-        self.out_file = Box::new(out_file);
+    pub fn with_out_file<W: Write + 'a>(self, out_file: W) -> Self {
+        self.with_sink(CsvSink::new(out_file))
+    }
+
+    /// Like [`LoggingScheduler::with_out_file`], but only writes once a buffer
+    /// fills up or the log is flushed.
+    pub fn with_buffered_out_file<W: Write + 'a>(self, out_file: W) -> Self {
+        self.with_sink(CsvSink::buffered(out_file))
+    }
+
+    /// Writes to `sink` on a separate thread, see [`ThreadedSink`].
+    pub fn with_threaded_sink(
+        self,
+        sink: impl Sink + Send + 'static,
+        capacity: usize,
+        overflow: Overflow,
+    ) -> Self {
+        self.with_sink(ThreadedSink::new(sink, capacity, overflow))
+    }
+
+    pub fn with_sink(mut self, sink: impl Sink + 'a) -> Self {
+        self.sink(sink);
         self
     }
 
     pub fn with_logging_enabled(mut self, enabled: bool) -> Self {
-        self.logging = enabled;
+        self.logging_enabled(enabled);
+        self
+    }
+
+    pub fn with_on_error(mut self, on_error: OnError) -> Self {
+        self.on_error(on_error);
+        self
+    }
+
+    /// Adds a value to the `run_start` records written by [`LogHandle::start`].
+    pub fn with_metadata(mut self, name: impl ToString, value: impl Into<Value>) -> Self {
+        self.metadata(name, value);
+        self
+    }
+
+    pub fn with_git_sha(self, sha: impl ToString) -> Self {
+        self.with_metadata("git_sha", sha.to_string())
+    }
+
+    /// Measures the time spent inside every recorder and writing what it
+    /// returns, and records them as `recorder_time/<recorder>` and
+    /// `sink_time/<recorder>` at the end of every iteration.
+    pub fn with_overhead_measured(mut self, measured: bool) -> Self {
+        self.overhead_measured(measured);
+        self
+    }
+
+    pub fn with_sampling(mut self, sampling: Sampling) -> Self {
+        self.sampling(sampling);
         self
     }
 
-    pub fn with_recorder<D: Recorder<L, N> + 'a>(mut self, datum: D) -> Self
+    pub fn with_recorder<M, D>(mut self, datum: D) -> Self
     where
         L: egg::Language,
         N: egg::Analysis<L>,
+        D: IntoRecorder<L, N, M>,
+        D::Recorder: 'a,
     {
-        self.recorders.push(Box::new(datum));
+        self.record(datum);
         self
     }
 
     pub fn identifier(&mut self, id: impl ToString) -> &mut Self {
-        self.identifier = id.to_string();
+        self.logger.borrow_mut().identifier = id.to_string();
         self
     }
 
     pub fn out_file(&mut self, out_file: impl Write + 'a) -> &mut Self {
-        self.out_file = Box::new(out_file);
+        self.sink(CsvSink::new(out_file))
+    }
+
+    pub fn sink(&mut self, sink: impl Sink + 'a) -> &mut Self {
+        self.logger.borrow_mut().sink = Box::new(sink);
         self
     }
 
     pub fn logging_enabled(&mut self, enabled: bool) -> &mut Self {
-        self.logging = enabled;
+        self.logger.borrow_mut().logging = enabled;
+        self
+    }
+
+    pub fn on_error(&mut self, on_error: OnError) -> &mut Self {
+        self.logger.borrow_mut().on_error = on_error;
+        self
+    }
+
+    pub fn metadata(&mut self, name: impl ToString, value: impl Into<Value>) -> &mut Self {
+        self.logger
+            .borrow_mut()
+            .metadata
+            .push((name.to_string(), value.into()));
+        self
+    }
+
+    pub fn sampling(&mut self, sampling: Sampling) -> &mut Self {
+        self.logger.borrow_mut().sampling = sampling;
+        self
+    }
+
+    pub fn overhead_measured(&mut self, measured: bool) -> &mut Self {
+        self.logger.borrow_mut().overhead = measured.then(Vec::new);
         self
     }
 
-    pub fn record<D: Recorder<L, N> + 'a>(&mut self, datum: D) -> &mut Self
+    /// Returns a handle for finishing the log and reading back errors once the
+    /// scheduler has been handed to a runner.
+    pub fn handle(&self) -> LogHandle<'a, L, N> {
+        LogHandle {
+            logger: Rc::clone(&self.logger),
+        }
+    }
+
+    pub fn record<M, D>(&mut self, datum: D) -> &mut Self
     where
         L: egg::Language,
         N: egg::Analysis<L>,
+        D: IntoRecorder<L, N, M>,
+        D::Recorder: 'a,
     {
-        self.recorders.push(Box::new(datum));
+        self.logger
+            .borrow_mut()
+            .recorders
+            .push(Box::new(datum.into_recorder()));
         self
     }
+}
 
+impl<'a, L, N> Logger<'a, L, N>
+where
+    L: egg::Language + std::fmt::Display,
+    N: egg::Analysis<L>,
+{
     fn write(
         &mut self,
         iteration: usize,
-        rule: &egg::Rewrite<L, N>,
-        typ: &str,
+        rule: Option<&egg::Rewrite<L, N>>,
+        phase: Phase,
         id: Cow<'static, str>,
-        datum: String,
-    ) where
-        L: egg::Language + std::fmt::Display,
-        N: egg::Analysis<L>,
-    {
-        writeln!(
-            &mut self.out_file,
-            "{},{},{},{},{},{},{}",
-            self.identifier,
+        datum: Value,
+    ) {
+        if !self.versioned {
+            self.versioned = true;
+            self.write(
+                0,
+                None,
+                Phase::RunStart,
+                "format_version".into(),
+                FORMAT_VERSION.into(),
+            );
+        }
+        // an earlier write, maybe the one above, may have failed and turned
+        // logging off
+        if !self.logging {
+            return;
+        }
+
+        if let Value::Multi(values) = datum {
+            for (name, value) in values {
+                self.write(iteration, rule, phase, format!("{id}/{name}").into(), value);
+            }
+            return;
+        }
+
+        let record = Record {
+            id: self.identifier.clone(),
             iteration,
-            rule.name,
-            rewrite_str(rule),
-            typ,
-            id,
-            datum
-        )
-        .unwrap();
+            rule_name: rule.map(|r| r.name.to_string()).unwrap_or_default(),
+            rule: rule.map(rewrite_str).unwrap_or_default(),
+            phase,
+            recorder: id.into_owned(),
+            value: datum,
+        };
+
+        if let Err(e) = self.sink.write(&record) {
+            self.fail(e);
+        }
+    }
+
+    /// Calls `hook` on every recorder, writing whatever they return.
+    fn record_all(
+        &mut self,
+        iteration: usize,
+        rule: Option<&egg::Rewrite<L, N>>,
+        phase: Phase,
+        mut hook: impl FnMut(&mut dyn Recorder<L, N>) -> Option<Value>,
+    ) {
+        let data: Vec<_> = self
+            .recorders
+            .iter_mut()
+            .map(|recorder| {
+                let start = Instant::now();
+                let datum = hook(recorder.as_mut());
+                let elapsed = start.elapsed();
+                (recorder.identifier(), datum, recorder.take_error(), elapsed)
+            })
+            .collect();
+
+        for (i, (id, datum, error, record)) in data.into_iter().enumerate() {
+            if let Some(e) = error {
+                self.fail(e);
+            }
+            let start = Instant::now();
+            if let Some(datum) = datum {
+                self.write(iteration, rule, phase, id, datum);
+            }
+            if let Some(overhead) = &mut self.overhead {
+                if overhead.len() <= i {
+                    overhead.resize(i + 1, Overhead::default());
+                }
+                overhead[i].record += record;
+                overhead[i].write += start.elapsed();
+            }
+        }
+    }
+
+    /// Tells every recorder how long the inner scheduler took, before the
+    /// `phase` hooks run.
+    fn inner_time(&mut self, phase: Phase, elapsed: Duration) {
+        for recorder in &mut self.recorders {
+            recorder.inner_time(phase, elapsed);
+        }
+    }
+
+    /// Records `iteration_end` for the previous iteration and `iteration_start`
+    /// for `iteration` the first time that `iteration` is seen.
+    fn enter_iteration(&mut self, iteration: usize, egraph: &egg::EGraph<L, N>) {
+        if self.iteration == Some(iteration) {
+            return;
+        }
+
+        if let Some(previous) = self.iteration {
+            self.iteration_end(previous, egraph);
+        }
+        self.iteration = Some(iteration);
+        self.recording_iteration = self.sampling.records_iteration(iteration);
+        self.iterations_only |= self.sampling.iterations_only(egraph.total_size());
+        self.recorded_rules.clear();
+        if !self.recording_iteration {
+            return;
+        }
+
+        self.record_all(iteration, None, Phase::IterationStart, |recorder| {
+            recorder.record_iteration_start(iteration, egraph)
+        });
+    }
+
+    fn iteration_end(&mut self, iteration: usize, egraph: &egg::EGraph<L, N>) {
+        if !self.recording_iteration {
+            return;
+        }
+        self.record_all(iteration, None, Phase::IterationEnd, |recorder| {
+            recorder.record_iteration_end(iteration, egraph)
+        });
+        self.write_overhead(iteration);
+    }
+
+    /// Whether to record the search for `rewrite`, and later its
+    /// application, in the current iteration.
+    fn sample_rule(&mut self, rewrite: &egg::Rewrite<L, N>) -> bool {
+        if !self.recording_iteration || self.iterations_only {
+            return false;
+        }
+        let sampled = self
+            .sampling
+            .records_rule(rewrite.name.as_str(), self.recorded_rules.len());
+        if sampled {
+            self.recorded_rules.insert(rewrite.name);
+        }
+        sampled
+    }
+
+    /// Writes the overhead of every recorder during `iteration`, if measured,
+    /// and starts measuring afresh.
+    fn write_overhead(&mut self, iteration: usize) {
+        if !self.logging {
+            return;
+        }
+        let Some(overhead) = self.overhead.as_mut().map(std::mem::take) else {
+            return;
+        };
+
+        let ids: Vec<_> = self.recorders.iter().map(|r| r.identifier()).collect();
+        for (id, overhead) in ids.into_iter().zip(overhead) {
+            let values = [
+                ("recorder_time", overhead.record),
+                ("sink_time", overhead.write),
+            ];
+            for (name, time) in values {
+                let name = format!("{name}/{id}").into();
+                self.write(iteration, None, Phase::IterationEnd, name, time.into());
+            }
+        }
+    }
+
+    fn fail(&mut self, error: Error) {
+        match self.on_error {
+            OnError::Panic => panic!("{error}"),
+            OnError::Disable => {
+                self.logging = false;
+                self.errors.push(error);
+            }
+            OnError::Collect => self.errors.push(error),
+        }
     }
 }
 
@@ -137,6 +600,26 @@ where
     }
 }
 
+fn stop_reason_str(reason: Option<&egg::StopReason>) -> String {
+    match reason {
+        None => "none".to_string(),
+        Some(egg::StopReason::Saturated) => "saturated".to_string(),
+        Some(egg::StopReason::IterationLimit(_)) => "iteration_limit".to_string(),
+        Some(egg::StopReason::NodeLimit(_)) => "node_limit".to_string(),
+        Some(egg::StopReason::TimeLimit(_)) => "time_limit".to_string(),
+        Some(egg::StopReason::Other(reason)) => format!("other: {reason}"),
+    }
+}
+
+fn hostname() -> Option<String> {
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|host| host.trim().to_string())
+        .filter(|host| !host.is_empty())
+}
+
 fn rewrite_str<L, N>(rewrite: &egg::Rewrite<L, N>) -> String
 where
     L: egg::Language + std::fmt::Display,
@@ -159,11 +642,6 @@ where
     N: egg::Analysis<L>,
 {
     fn can_stop(&mut self, iteration: usize) -> bool {
-        // if disabled, just call underlying scheduler
-        if !self.logging {
-            return self.inner_scheduler.can_stop(iteration);
-        }
-
         self.inner_scheduler.can_stop(iteration)
     }
 
@@ -174,47 +652,33 @@ where
         rewrite: &'s egg::Rewrite<L, N>,
     ) -> Vec<egg::SearchMatches<'s, L>> {
         // if disabled, just call underlying scheduler
-        if !self.logging {
+        if !self.logger.borrow().logging {
             return self
                 .inner_scheduler
                 .search_rewrite(iteration, egraph, rewrite);
         }
 
-        self.recorders
-            .iter()
-            .map(|recorder| {
-                (
-                    recorder.identifier(),
-                    recorder.record_before_search(iteration, egraph, rewrite),
-                )
-            })
-            .collect::<Vec<_>>()
-            .into_iter()
-            .for_each(|(id, datum)| {
-                if let Some(datum) = datum {
-                    self.write(iteration, rewrite, "before_search", id, datum);
-                }
-            });
+        let mut logger = self.logger.borrow_mut();
+        logger.enter_iteration(iteration, egraph);
+        if !logger.sample_rule(rewrite) {
+            return self
+                .inner_scheduler
+                .search_rewrite(iteration, egraph, rewrite);
+        }
 
+        logger.record_all(iteration, Some(rewrite), Phase::BeforeSearch, |recorder| {
+            recorder.record_before_search(iteration, egraph, rewrite)
+        });
+
+        let start = Instant::now();
         let matches = self
             .inner_scheduler
             .search_rewrite(iteration, egraph, rewrite);
+        logger.inner_time(Phase::AfterSearch, start.elapsed());
 
-        self.recorders
-            .iter()
-            .map(|recorder| {
-                (
-                    recorder.identifier(),
-                    recorder.record_after_search(iteration, egraph, rewrite, &matches),
-                )
-            })
-            .collect::<Vec<_>>()
-            .into_iter()
-            .for_each(|(id, datum)| {
-                if let Some(datum) = datum {
-                    self.write(iteration, rewrite, "before_search", id, datum);
-                }
-            });
+        logger.record_all(iteration, Some(rewrite), Phase::AfterSearch, |recorder| {
+            recorder.record_after_search(iteration, egraph, rewrite, &matches)
+        });
 
         matches
     }
@@ -227,47 +691,32 @@ where
         matches: Vec<egg::SearchMatches<L>>,
     ) -> usize {
         // if disabled, just call underlying scheduler
-        if !self.logging {
+        if !self.logger.borrow().logging {
             return self
                 .inner_scheduler
                 .apply_rewrite(iteration, egraph, rewrite, matches);
         }
 
-        self.recorders
-            .iter()
-            .map(|recorder| {
-                (
-                    recorder.identifier(),
-                    recorder.record_before_rewrite(iteration, egraph, rewrite, &matches),
-                )
-            })
-            .collect::<Vec<_>>()
-            .into_iter()
-            .for_each(|(id, datum)| {
-                if let Some(datum) = datum {
-                    self.write(iteration, rewrite, "before_rewrite", id, datum);
-                }
-            });
+        let mut logger = self.logger.borrow_mut();
+        if !logger.recorded_rules.contains(&rewrite.name) {
+            return self
+                .inner_scheduler
+                .apply_rewrite(iteration, egraph, rewrite, matches);
+        }
 
+        logger.record_all(iteration, Some(rewrite), Phase::BeforeRewrite, |recorder| {
+            recorder.record_before_rewrite(iteration, egraph, rewrite, &matches)
+        });
+
+        let start = Instant::now();
         let n_matches = self
             .inner_scheduler
             .apply_rewrite(iteration, egraph, rewrite, matches);
+        logger.inner_time(Phase::AfterRewrite, start.elapsed());
 
-        self.recorders
-            .iter()
-            .map(|recorder| {
-                (
-                    recorder.identifier(),
-                    recorder.record_after_rewrite(iteration, egraph, rewrite, n_matches),
-                )
-            })
-            .collect::<Vec<_>>()
-            .into_iter()
-            .for_each(|(id, datum)| {
-                if let Some(datum) = datum {
-                    self.write(iteration, rewrite, "after_rewrite", id, datum);
-                }
-            });
+        logger.record_all(iteration, Some(rewrite), Phase::AfterRewrite, |recorder| {
+            recorder.record_after_rewrite(iteration, egraph, rewrite, n_matches)
+        });
 
         n_matches
     }
@@ -0,0 +1,71 @@
+use std::{fmt, str::FromStr};
+
+/// The version of the log format written by this crate.
+///
+/// Version 1 logs have no version marker and label `after_search` records as
+/// `before_search`.
+pub const FORMAT_VERSION: i64 = 2;
+
+/// When during a run a record was taken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    RunStart,
+    IterationStart,
+    BeforeSearch,
+    AfterSearch,
+    BeforeRewrite,
+    AfterRewrite,
+    IterationEnd,
+    RunEnd,
+}
+
+impl Phase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Phase::RunStart => "run_start",
+            Phase::IterationStart => "iteration_start",
+            Phase::BeforeSearch => "before_search",
+            Phase::AfterSearch => "after_search",
+            Phase::BeforeRewrite => "before_rewrite",
+            Phase::AfterRewrite => "after_rewrite",
+            Phase::IterationEnd => "iteration_end",
+            Phase::RunEnd => "run_end",
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownPhase(pub String);
+
+impl fmt::Display for UnknownPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown phase `{}`", self.0)
+    }
+}
+
+impl std::error::Error for UnknownPhase {}
+
+impl FromStr for Phase {
+    type Err = UnknownPhase;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "run_start" => Phase::RunStart,
+            "iteration_start" => Phase::IterationStart,
+            "before_search" => Phase::BeforeSearch,
+            "after_search" => Phase::AfterSearch,
+            "before_rewrite" => Phase::BeforeRewrite,
+            "after_rewrite" => Phase::AfterRewrite,
+            "iteration_end" => Phase::IterationEnd,
+            "run_end" => Phase::RunEnd,
+            _ => return Err(UnknownPhase(s.to_string())),
+        })
+    }
+}
@@ -0,0 +1,369 @@
+//! Reading logs back into [`Record`]s.
+//!
+//! `.csv` logs don't say what type their values are, so values are guessed
+//! with [`guess_value`]: durations come back as [`Value::Float`] milliseconds.
+//! `.jsonl` logs keep their types.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::Path,
+    time::Duration,
+};
+
+use crate::{
+    logging_scheduler::DEFAULT_IDENTIFIER, sinks::CSV_HEADERS, Error, Phase, Record, Result, Value,
+};
+
+/// The records of a log, in the order they were written.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Log {
+    pub records: Vec<Record>,
+}
+
+/// Everything a run recorded about itself in its `run_start` and `run_end`
+/// rows, such as `stop_reason` or `final_enodes`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Summary {
+    pub id: String,
+    pub values: BTreeMap<String, Value>,
+}
+
+impl Summary {
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.values.get(name)
+    }
+}
+
+/// Reads a `.jsonl` log, or a `.csv` log for any other extension.
+pub fn read(path: impl AsRef<Path>) -> Result<Log> {
+    let file = File::open(path.as_ref())?;
+    if path.as_ref().extension().is_some_and(|ext| ext == "jsonl") {
+        read_json_lines(file)
+    } else {
+        read_csv(file)
+    }
+}
+
+/// Reads every `.csv` and `.jsonl` log in `dir` into one log. Runs without an
+/// identifier are named after their file, so that runs from different files
+/// stay apart.
+pub fn read_dir(dir: impl AsRef<Path>) -> Result<Log> {
+    let mut paths = std::fs::read_dir(dir)?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<_>>>()?;
+    paths.retain(|path| {
+        path.is_file()
+            && path
+                .extension()
+                .is_some_and(|ext| ext == "csv" || ext == "jsonl")
+    });
+    paths.sort();
+
+    let mut records = vec![];
+    for path in paths {
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        for mut record in read(&path)?.records {
+            if record.id == DEFAULT_IDENTIFIER {
+                record.id = name.to_string();
+            }
+            records.push(record);
+        }
+    }
+    Ok(Log { records })
+}
+
+/// Reads a csv log, with or without a header line.
+pub fn read_csv(input: impl Read) -> Result<Log> {
+    let records = csv_records(input).collect::<Result<_>>()?;
+    Ok(Log { records })
+}
+
+pub fn read_json_lines(input: impl Read) -> Result<Log> {
+    let records = json_lines_records(input).collect::<Result<_>>()?;
+    Ok(Log { records })
+}
+
+/// Parses the rows of a csv log one at a time, so that a bad row can be
+/// skipped.
+///
+/// A first row whose `iteration` isn't a number is taken to be a header line,
+/// and its column names are used to find the fields of the following rows.
+pub fn csv_records(input: impl Read) -> impl Iterator<Item = Result<Record>> {
+    let mut rows = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(input)
+        .into_records()
+        .peekable();
+
+    let header = rows
+        .next_if(|first| {
+            first
+                .as_ref()
+                .is_ok_and(|first| first.get(1).is_some_and(|it| it.parse::<u64>().is_err()))
+        })
+        .and_then(|header| header.ok());
+    let columns: Vec<usize> = CSV_HEADERS
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            header
+                .as_ref()
+                .and_then(|header| header.iter().position(|column| column == *name))
+                .unwrap_or(i)
+        })
+        .collect();
+
+    rows.map(move |row| {
+        let row = row?;
+        let field = |i: usize| {
+            row.get(columns[i])
+                .ok_or_else(|| Error::Malformed(format!("missing `{}` in {row:?}", CSV_HEADERS[i])))
+        };
+        Ok(Record {
+            id: field(0)?.to_string(),
+            iteration: field(1)?
+                .parse()
+                .map_err(|_| Error::Malformed(format!("bad iteration in {row:?}")))?,
+            rule_name: field(2)?.to_string(),
+            rule: field(3)?.to_string(),
+            phase: field(4)?
+                .parse()
+                .map_err(|e| Error::Malformed(format!("{e} in {row:?}")))?,
+            recorder: field(5)?.to_string(),
+            value: guess_value(field(6)?),
+        })
+    })
+}
+
+/// Parses the lines of a `.jsonl` log one at a time, skipping blank lines.
+pub fn json_lines_records(input: impl Read) -> impl Iterator<Item = Result<Record>> {
+    BufReader::new(input)
+        .lines()
+        .filter(|line| !line.as_ref().is_ok_and(|line| line.trim().is_empty()))
+        .map(|line| parse_json_line(&line?))
+}
+
+#[derive(serde::Deserialize)]
+struct JsonRecord {
+    id: String,
+    iteration: usize,
+    rule_name: String,
+    rule: String,
+    when: Phase,
+    name: String,
+    value: serde_json::Value,
+    #[serde(rename = "type", default)]
+    typ: Option<String>,
+}
+
+/// Parses one line of a `.jsonl` log.
+pub fn parse_json_line(line: &str) -> Result<Record> {
+    let record: JsonRecord = serde_json::from_str(line)?;
+    Ok(Record {
+        id: record.id,
+        iteration: record.iteration,
+        rule_name: record.rule_name,
+        rule: record.rule,
+        phase: record.when,
+        recorder: record.name,
+        value: json_value(record.value, record.typ.as_deref()),
+    })
+}
+
+/// Turns a JSON value back into a [`Value`], given the type it was written
+/// with, if known.
+fn json_value(value: serde_json::Value, typ: Option<&str>) -> Value {
+    use serde_json::Value as Json;
+
+    match (typ, value) {
+        (Some("duration"), Json::Number(ms)) => {
+            let ms = ms.as_f64().unwrap_or_default();
+            Value::Duration(Duration::try_from_secs_f64(ms / 1000.0).unwrap_or_default())
+        }
+        (Some("float"), Json::Number(n)) => Value::Float(n.as_f64().unwrap_or_default()),
+        (_, Json::Number(n)) => match n.as_i64() {
+            Some(n) => Value::Int(n),
+            None => Value::Float(n.as_f64().unwrap_or_default()),
+        },
+        (_, Json::Bool(b)) => Value::Bool(b),
+        (_, Json::String(s)) => Value::Str(s),
+        (_, Json::Null) => Value::Str(String::new()),
+        (_, Json::Array(values)) => Value::List(
+            values
+                .into_iter()
+                .map(|value| json_value(value, None))
+                .collect(),
+        ),
+        (_, Json::Object(values)) => Value::Map(
+            values
+                .into_iter()
+                .map(|(name, value)| (name, json_value(value, None)))
+                .collect(),
+        ),
+    }
+}
+
+/// Guesses the type of a value read from a `.csv` log.
+pub fn guess_value(value: &str) -> Value {
+    if let Ok(n) = value.parse() {
+        Value::Int(n)
+    } else if let Ok(n) = value.parse() {
+        Value::Float(n)
+    } else if let Ok(b) = value.parse() {
+        Value::Bool(b)
+    } else {
+        // lists and maps are written as JSON
+        match serde_json::from_str(value) {
+            Ok(json @ (serde_json::Value::Array(_) | serde_json::Value::Object(_))) => {
+                json_value(json, None)
+            }
+            _ => Value::Str(value.to_string()),
+        }
+    }
+}
+
+impl Log {
+    /// The identifiers of the runs in the log, in the order they first appear.
+    pub fn runs(&self) -> Vec<&str> {
+        self.group_runs().into_iter().map(|(id, _)| id).collect()
+    }
+
+    /// The indices of the records of every run, in the order the runs first
+    /// appear, found in a single pass over the log.
+    fn group_runs(&self) -> Vec<(&str, Vec<usize>)> {
+        let mut runs: Vec<(&str, Vec<usize>)> = vec![];
+        let mut positions: HashMap<&str, usize> = HashMap::new();
+        for (i, record) in self.records.iter().enumerate() {
+            let position = *positions.entry(&record.id).or_insert_with(|| {
+                runs.push((&record.id, vec![]));
+                runs.len() - 1
+            });
+            runs[position].1.push(i);
+        }
+        runs
+    }
+
+    /// Every run on its own, in the order the runs first appear.
+    fn split_runs(&self) -> Vec<(&str, Log)> {
+        self.group_runs()
+            .into_iter()
+            .map(|(id, indices)| {
+                let records = indices.into_iter().map(|i| self.records[i].clone());
+                (
+                    id,
+                    Log {
+                        records: records.collect(),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// The records of the run `id`.
+    pub fn run(&self, id: &str) -> Log {
+        Log {
+            records: self
+                .records
+                .iter()
+                .filter(|record| record.id == id)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Pairs up the runs of `self` and `other` that have the same id, in the
+    /// order they appear. A run missing from one log is paired with an empty
+    /// log. When both logs hold a single run, those are paired whatever their
+    /// ids.
+    pub(crate) fn pair_runs(&self, other: &Log) -> Vec<(String, Log, Log)> {
+        let (ours, theirs) = (self.split_runs(), other.split_runs());
+        if let ([(id, _)], [_]) = (&ours[..], &theirs[..]) {
+            return vec![(id.to_string(), self.clone(), other.clone())];
+        }
+
+        let mut theirs_by_id: HashMap<&str, Log> = HashMap::new();
+        let mut only_theirs = vec![];
+        for (id, log) in theirs {
+            if !ours.iter().any(|(ours, _)| *ours == id) {
+                only_theirs.push(id);
+            }
+            theirs_by_id.insert(id, log);
+        }
+        let mut pairs: Vec<_> = ours
+            .into_iter()
+            .map(|(id, log)| {
+                let theirs = theirs_by_id.remove(id).unwrap_or_default();
+                (id.to_string(), log, theirs)
+            })
+            .collect();
+        pairs.extend(only_theirs.into_iter().map(|id| {
+            let theirs = theirs_by_id.remove(id).unwrap_or_default();
+            (id.to_string(), Log::default(), theirs)
+        }));
+        pairs
+    }
+
+    /// The value of `recorder` at the end of every iteration.
+    pub fn series(&self, recorder: &str) -> Vec<(usize, &Value)> {
+        self.records
+            .iter()
+            .filter(|record| record.phase == Phase::IterationEnd && record.recorder == recorder)
+            .map(|record| (record.iteration, &record.value))
+            .collect()
+    }
+
+    /// The last value of `recorder` recorded at the end of an iteration.
+    pub fn last(&self, recorder: &str) -> Option<&Value> {
+        self.series(recorder).last().map(|(_, value)| *value)
+    }
+
+    /// The values of `recorder` in `phase`, for every rule and iteration.
+    pub fn by_rule(&self, recorder: &str, phase: Phase) -> BTreeMap<&str, Vec<(usize, &Value)>> {
+        let mut rules: BTreeMap<&str, Vec<_>> = BTreeMap::new();
+        for record in &self.records {
+            if record.phase == phase && record.recorder == recorder {
+                rules
+                    .entry(&record.rule_name)
+                    .or_default()
+                    .push((record.iteration, &record.value));
+            }
+        }
+        rules
+    }
+
+    /// What every run recorded about itself, in the order the runs appear.
+    pub fn summaries(&self) -> Vec<Summary> {
+        self.group_runs()
+            .into_iter()
+            .map(|(id, indices)| Summary {
+                id: id.to_string(),
+                values: indices
+                    .into_iter()
+                    .map(|i| &self.records[i])
+                    .filter(|record| matches!(record.phase, Phase::RunStart | Phase::RunEnd))
+                    .map(|record| (record.recorder.clone(), record.value.clone()))
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// The format version the log was written with. Logs written before
+    /// versions were recorded are version 1.
+    pub fn format_version(&self) -> i64 {
+        self.records
+            .iter()
+            .find(|record| record.phase == Phase::RunStart && record.recorder == "format_version")
+            .and_then(|record| match record.value {
+                Value::Int(version) => Some(version),
+                _ => None,
+            })
+            .unwrap_or(1)
+    }
+}
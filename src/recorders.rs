@@ -1,6 +1,18 @@
-use std::{borrow::Cow, time::Instant};
+use std::{
+    borrow::Cow,
+    cell::Cell,
+    collections::BTreeMap,
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
-use crate::{impl_recorder, AfterRewriteArgs, BeforeRewriteArgs, Recorder};
+use crate::{
+    impl_recorder, snapshot, AfterRewriteArgs, AfterSearchArgs, BeforeRewriteArgs, IterationArgs,
+    Phase, Recorder, Value,
+};
 
 pub struct Timestamp {
     start: Instant,
@@ -24,10 +36,71 @@ impl_recorder! {
     Timestamp;
     identifier => "time",
     before_rewrite => |ts: &Self, _| {
-        Some(format!("{}", ts.start.elapsed().as_millis()))
+        Some(ts.start.elapsed())
     },
     after_rewrite => |ts: &Self, _| {
-        Some(format!("{}", ts.start.elapsed().as_millis()))
+        Some(ts.start.elapsed())
+    },
+    iteration_end => |ts: &Self, _| {
+        Some(ts.start.elapsed())
+    }
+}
+
+/// Time the wrapped scheduler spent searching for each rule, recorded as
+/// `rule_search_time` to keep it apart from the run's total `search_time`.
+#[derive(Default)]
+pub struct SearchTime {
+    elapsed: Option<Duration>,
+}
+
+impl<L: egg::Language, N: egg::Analysis<L>> Recorder<L, N> for SearchTime {
+    fn identifier(&self) -> Cow<'static, str> {
+        "rule_search_time".into()
+    }
+
+    fn inner_time(&mut self, phase: Phase, elapsed: Duration) {
+        if phase == Phase::AfterSearch {
+            self.elapsed = Some(elapsed);
+        }
+    }
+
+    fn record_after_search<'r>(
+        &mut self,
+        _iteration: usize,
+        _egraph: &'r egg::EGraph<L, N>,
+        _rewrite: &'r egg::Rewrite<L, N>,
+        _matches: &[egg::SearchMatches<L>],
+    ) -> Option<Value> {
+        self.elapsed.take().map(Value::from)
+    }
+}
+
+/// Time the wrapped scheduler spent applying each rule, recorded as
+/// `rule_apply_time` to keep it apart from the run's total `apply_time`.
+#[derive(Default)]
+pub struct ApplyTime {
+    elapsed: Option<Duration>,
+}
+
+impl<L: egg::Language, N: egg::Analysis<L>> Recorder<L, N> for ApplyTime {
+    fn identifier(&self) -> Cow<'static, str> {
+        "rule_apply_time".into()
+    }
+
+    fn inner_time(&mut self, phase: Phase, elapsed: Duration) {
+        if phase == Phase::AfterRewrite {
+            self.elapsed = Some(elapsed);
+        }
+    }
+
+    fn record_after_rewrite(
+        &mut self,
+        _iteration: usize,
+        _egraph: &mut egg::EGraph<L, N>,
+        _rewrite: &egg::Rewrite<L, N>,
+        _n_matches: usize,
+    ) -> Option<Value> {
+        self.elapsed.take().map(Value::from)
     }
 }
 
@@ -35,10 +108,13 @@ impl_recorder! {
     pub struct NumberENodes;
     identifier => "enodes",
     before_rewrite => |_, x: BeforeRewriteArgs<_, _>| {
-        Some(format!("{}", x.egraph.total_size()))
+        Some(x.egraph.total_size())
     },
     after_rewrite => |_, x: AfterRewriteArgs<_, _>| {
-        Some(format!("{}", x.egraph.total_size()))
+        Some(x.egraph.total_size())
+    },
+    iteration_end => |_, x: IterationArgs<_, _>| {
+        Some(x.egraph.total_size())
     }
 }
 
@@ -46,10 +122,241 @@ impl_recorder! {
     pub struct NumberEClasses;
     identifier => "eclasses",
     before_rewrite => |_, x: BeforeRewriteArgs<_, _>| {
-        Some(format!("{}", x.egraph.number_of_classes()))
+        Some(x.egraph.number_of_classes())
     },
     after_rewrite => |_, x: AfterRewriteArgs<_, _>| {
-        Some(format!("{}", x.egraph.number_of_classes()))
+        Some(x.egraph.number_of_classes())
+    },
+    iteration_end => |_, x: IterationArgs<_, _>| {
+        Some(x.egraph.number_of_classes())
+    }
+}
+
+impl_recorder! {
+    /// How many e-nodes each e-class holds, at the end of every iteration.
+    ///
+    /// Records the `min`, `median`, `p90`, `p99` and `max` class size, and a
+    /// `histogram` whose `i`th entry counts the classes with `2^i` to
+    /// `2^(i+1) - 1` nodes, each as its own `eclass_sizes/<name>` row.
+    pub struct EClassSizes;
+    identifier => "eclass_sizes",
+    iteration_end => |_, x: IterationArgs<_, _>| {
+        let mut sizes: Vec<usize> = x.egraph.classes().map(|class| class.len()).collect();
+        sizes.sort_unstable();
+        class_size_distribution(&sizes)
+    }
+}
+
+/// `sizes` must be sorted.
+fn class_size_distribution(sizes: &[usize]) -> Option<Value> {
+    let (&min, &max) = (sizes.first()?, sizes.last()?);
+    let percentile = |p: usize| sizes[(sizes.len() * p).div_ceil(100).max(1) - 1];
+
+    let mut histogram: Vec<usize> = vec![];
+    for size in sizes {
+        let bucket = size.max(&1).ilog2() as usize;
+        if histogram.len() <= bucket {
+            histogram.resize(bucket + 1, 0);
+        }
+        histogram[bucket] += 1;
+    }
+
+    Some(Value::Multi(vec![
+        ("min".into(), min.into()),
+        ("median".into(), percentile(50).into()),
+        ("p90".into(), percentile(90).into()),
+        ("p99".into(), percentile(99).into()),
+        ("max".into(), max.into()),
+        ("histogram".into(), histogram.into()),
+    ]))
+}
+
+/// Number of e-nodes of every operator, at the end of every iteration.
+///
+/// Operators are told apart by how an e-node displays, so `(+ a b)` and
+/// `(+ c d)` count as the same operator. Leaves, such as constants and
+/// variables, are all counted together as `leaves`, so that the number of rows
+/// doesn't grow with the number of distinct constants. Each count is written
+/// as its own `operators/<op>` row.
+pub struct OperatorCounts;
+
+impl<L, N> Recorder<L, N> for OperatorCounts
+where
+    L: egg::Language + std::fmt::Display,
+    N: egg::Analysis<L>,
+{
+    fn identifier(&self) -> Cow<'static, str> {
+        "operators".into()
+    }
+
+    fn record_iteration_end(
+        &mut self,
+        _iteration: usize,
+        egraph: &egg::EGraph<L, N>,
+    ) -> Option<Value> {
+        let mut counts = BTreeMap::<String, usize>::new();
+        for node in egraph.classes().flat_map(|class| class.iter()) {
+            let op = if node.is_leaf() {
+                "leaves".to_string()
+            } else {
+                node.to_string()
+            };
+            *counts.entry(op).or_default() += 1;
+        }
+        Some(Value::multi(counts))
+    }
+}
+
+impl_recorder! {
+    /// Number of e-classes a rule's searcher matched.
+    pub struct MatchedEClasses;
+    identifier => "matched_eclasses",
+    after_search => |_, x: AfterSearchArgs<_, _>| {
+        Some(x.matches.len())
+    }
+}
+
+impl_recorder! {
+    /// Number of substitutions found by a rule's searcher, summed over every
+    /// matched e-class.
+    pub struct Substitutions;
+    identifier => "substitutions",
+    after_search => |_, x: AfterSearchArgs<_, _>| {
+        Some(x.matches.iter().map(|m| m.substs.len()).sum::<usize>())
+    }
+}
+
+impl_recorder! {
+    /// Number of substitutions whose application actually changed the e-graph.
+    ///
+    /// Compare with [`Substitutions`] to find rules that match a lot but rarely
+    /// add anything.
+    pub struct Applications;
+    identifier => "applications",
+    after_rewrite => |_, x: AfterRewriteArgs<_, _>| {
+        Some(x.n_matches)
+    }
+}
+
+/// When [`BestProgram`] and [`BestExpr`] extract the best program.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Extraction {
+    /// Before every rule is applied, and at the end of every iteration.
+    #[default]
+    EveryRule,
+    /// Like `EveryRule`, but reuse the last result while the e-graph looks
+    /// unchanged: it has as many e-nodes and e-classes as last time, and it
+    /// is still as clean.
+    OnChange,
+    /// Only at the end of every iteration.
+    IterationEnd,
+}
+
+#[derive(PartialEq)]
+struct EGraphState {
+    enodes: usize,
+    eclasses: usize,
+    clean: bool,
+}
+
+impl EGraphState {
+    fn of<L: egg::Language, N: egg::Analysis<L>>(egraph: &egg::EGraph<L, N>) -> Self {
+        EGraphState {
+            enodes: egraph.total_size(),
+            eclasses: egraph.number_of_classes(),
+            clean: egraph.clean,
+        }
+    }
+}
+
+/// Decides when to extract, caches the result and keeps track of the time
+/// spent extracting.
+struct Extractions<T> {
+    mode: Extraction,
+    sample_every: usize,
+    rules_seen: usize,
+    last: Option<(EGraphState, T)>,
+    spent: Rc<Cell<Duration>>,
+}
+
+impl<T: Clone> Extractions<T> {
+    fn new() -> Self {
+        Extractions {
+            mode: Extraction::default(),
+            sample_every: 1,
+            rules_seen: 0,
+            last: None,
+            spent: Rc::default(),
+        }
+    }
+
+    fn before_rewrite(&mut self) -> bool {
+        if self.mode == Extraction::IterationEnd {
+            return false;
+        }
+        self.rules_seen += 1;
+        (self.rules_seen - 1).is_multiple_of(self.sample_every)
+    }
+
+    fn extract<L, N>(&mut self, egraph: &egg::EGraph<L, N>, extract: impl FnOnce() -> T) -> T
+    where
+        L: egg::Language,
+        N: egg::Analysis<L>,
+    {
+        let state = EGraphState::of(egraph);
+        if let Some((last, result)) = &self.last {
+            if self.mode == Extraction::OnChange && *last == state {
+                return result.clone();
+            }
+        }
+
+        let start = Instant::now();
+        let result = extract();
+        self.spent.set(self.spent.get() + start.elapsed());
+        self.last = Some((state, result.clone()));
+        result
+    }
+}
+
+impl_recorder! {
+    /// Time a [`BestProgram`] or [`BestExpr`] spent extracting during each
+    /// iteration, recorded at the end of the iteration. Add it after the
+    /// recorder it measures so that the last extraction of an iteration is
+    /// counted.
+    pub struct ExtractionOverhead {
+        spent: Rc<Cell<Duration>> = Rc::default(),
+    };
+    identifier => "extraction_time",
+    iteration_end => |o: &mut Self, _: IterationArgs<_, _>| {
+        Some(o.spent.take())
+    }
+}
+
+/// Wraps a cost function whose costs can only be displayed, such as one with
+/// `NotNan<f64>` costs, so that [`BestProgram`] and [`BestExpr`] record them
+/// with [`Value::display`]. Costs that convert `Into<Value>` are recorded
+/// typed and don't need it.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Displayed<C>(pub C);
+
+impl<L, C> egg::CostFunction<L> for Displayed<C>
+where
+    L: egg::Language,
+    C: egg::CostFunction<L>,
+{
+    type Cost = Displayed<C::Cost>;
+
+    fn cost<F>(&mut self, enode: &L, mut costs: F) -> Self::Cost
+    where
+        F: FnMut(egg::Id) -> Self::Cost,
+    {
+        Displayed(self.0.cost(enode, |id| costs(id).0))
+    }
+}
+
+impl<T: std::fmt::Display> From<Displayed<T>> for Value {
+    fn from(value: Displayed<T>) -> Self {
+        Value::display(value.0)
     }
 }
 
@@ -57,6 +364,7 @@ pub struct BestProgram<C> {
     cost_fn: Box<dyn Fn() -> C>,
     root: egg::Id,
     rebuild: bool,
+    extractions: Extractions<Value>,
 }
 
 impl<C> BestProgram<C> {
@@ -69,6 +377,7 @@ impl<C> BestProgram<C> {
             cost_fn: Box::new(move || cost_fn_clone.clone()),
             root,
             rebuild: false,
+            extractions: Extractions::new(),
         }
     }
 
@@ -77,13 +386,58 @@ impl<C> BestProgram<C> {
             cost_fn: Box::new(cost_fn),
             root,
             rebuild: false,
+            extractions: Extractions::new(),
         }
     }
 
+    /// Like [`BestProgram::new_with`], for costs that can only be displayed. See
+    /// [`Displayed`].
+    pub fn displayed(
+        cost_fn: impl Fn() -> C + 'static,
+        root: egg::Id,
+    ) -> BestProgram<Displayed<C>> {
+        BestProgram::new_with(move || Displayed(cost_fn()), root)
+    }
+
+    #[deprecated(
+        note = "the cost is now recorded at the end of every iteration, after egg rebuilds"
+    )]
     pub fn with_rebuild(mut self, rebuild: bool) -> Self {
         self.rebuild = rebuild;
         self
     }
+
+    pub fn with_extraction(mut self, extraction: Extraction) -> Self {
+        self.extractions.mode = extraction;
+        self
+    }
+
+    /// Only extracts before every `n`th rule application.
+    pub fn sample_every(mut self, n: usize) -> Self {
+        self.extractions.sample_every = n.max(1);
+        self
+    }
+
+    /// A recorder for the time this one spends extracting.
+    pub fn overhead(&self) -> ExtractionOverhead {
+        ExtractionOverhead {
+            spent: self.extractions.spent.clone(),
+        }
+    }
+
+    fn record<L, N>(&mut self, egraph: &egg::EGraph<L, N>) -> Value
+    where
+        L: egg::Language,
+        N: egg::Analysis<L>,
+        C: egg::CostFunction<L>,
+        C::Cost: Into<Value>,
+    {
+        let (cost_fn, root) = (&self.cost_fn, self.root);
+        self.extractions.extract(egraph, || {
+            let extractor = egg::Extractor::new(egraph, cost_fn());
+            extractor.find_best_cost(root).into()
+        })
+    }
 }
 
 impl<L, N, C> Recorder<L, N> for BestProgram<C>
@@ -91,36 +445,337 @@ where
     L: egg::Language,
     N: egg::Analysis<L>,
     C: egg::CostFunction<L>,
-    <C as egg::CostFunction<L>>::Cost: std::fmt::Display,
+    <C as egg::CostFunction<L>>::Cost: Into<Value>,
 {
     fn identifier(&self) -> Cow<'static, str> {
         "cost".into()
     }
 
     fn record_before_rewrite(
-        &self,
+        &mut self,
         _iteration: usize,
         egraph: &mut egg::EGraph<L, N>,
         _rewrite: &egg::Rewrite<L, N>,
         _matches: &[egg::SearchMatches<L>],
-    ) -> Option<String> {
-        let extractor = egg::Extractor::new(egraph, (self.cost_fn)());
-        Some(format!("{}", extractor.find_best_cost(self.root)))
+    ) -> Option<Value> {
+        if !self.extractions.before_rewrite() {
+            return None;
+        }
+        Some(self.record(egraph))
     }
 
     fn record_after_rewrite(
-        &self,
+        &mut self,
         _iteration: usize,
         egraph: &mut egg::EGraph<L, N>,
         _rewrite: &egg::Rewrite<L, N>,
         _n_matches: usize,
-    ) -> Option<String> {
+    ) -> Option<Value> {
         if self.rebuild {
             egraph.rebuild();
-            let extractor = egg::Extractor::new(egraph, (self.cost_fn)());
-            Some(format!("{}", extractor.find_best_cost(self.root)))
+            Some(self.record(egraph))
         } else {
             None
         }
     }
+    fn record_iteration_end(
+        &mut self,
+        _iteration: usize,
+        egraph: &egg::EGraph<L, N>,
+    ) -> Option<Value> {
+        Some(self.record(egraph))
+    }
+}
+
+type ClassData<D> = Box<dyn Fn(&D) -> Option<String>>;
+
+/// Dumps the whole e-graph to a side file every few iterations, or whenever a
+/// [`SnapshotTrigger`] asks for one.
+///
+/// Snapshots are taken at the end of an iteration and written to
+/// `<dir>/iteration-<n>.json` in the format described in [`snapshot`]. The
+/// recorded value is the snapshot's path. `egg-viz` lists the snapshots kept
+/// in a `<log>.snapshots` directory beside a log, which is what
+/// [`Snapshot::beside`] uses.
+pub struct Snapshot<D> {
+    dir: PathBuf,
+    every: Option<usize>,
+    roots: Vec<egg::Id>,
+    class_data: ClassData<D>,
+    requested: Rc<Cell<bool>>,
+    error: Option<crate::Error>,
+}
+
+/// Asks a [`Snapshot`] recorder for a snapshot at the end of the current
+/// iteration.
+#[derive(Clone)]
+pub struct SnapshotTrigger(Rc<Cell<bool>>);
+
+impl SnapshotTrigger {
+    pub fn request(&self) {
+        self.0.set(true);
+    }
+}
+
+impl<D> Snapshot<D> {
+    /// Writes snapshots to `dir`, which is created when the first snapshot is
+    /// taken. No snapshots are taken until [`Snapshot::every`] or a
+    /// [`SnapshotTrigger`] asks for them.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Snapshot {
+            dir: dir.into(),
+            every: None,
+            roots: vec![],
+            class_data: Box::new(|_| None),
+            requested: Rc::default(),
+            error: None,
+        }
+    }
+
+    /// Writes snapshots where `egg-viz` looks for the snapshots of `log`.
+    pub fn beside(log: impl AsRef<Path>) -> Self {
+        Snapshot::new(log.as_ref().with_extension("snapshots"))
+    }
+
+    /// Takes a snapshot every `n` iterations, starting with the first.
+    pub fn every(mut self, n: usize) -> Self {
+        self.every = Some(n.max(1));
+        self
+    }
+
+    pub fn with_roots(mut self, roots: impl IntoIterator<Item = egg::Id>) -> Self {
+        self.roots = roots.into_iter().collect();
+        self
+    }
+
+    /// Formats the analysis data of every e-class.
+    pub fn with_class_data(mut self, class_data: impl Fn(&D) -> Option<String> + 'static) -> Self {
+        self.class_data = Box::new(class_data);
+        self
+    }
+
+    pub fn trigger(&self) -> SnapshotTrigger {
+        SnapshotTrigger(self.requested.clone())
+    }
+}
+
+impl<L, N> Recorder<L, N> for Snapshot<N::Data>
+where
+    L: egg::Language + std::fmt::Display,
+    N: egg::Analysis<L>,
+{
+    fn identifier(&self) -> Cow<'static, str> {
+        "snapshot".into()
+    }
+
+    fn record_iteration_end(
+        &mut self,
+        iteration: usize,
+        egraph: &egg::EGraph<L, N>,
+    ) -> Option<Value> {
+        let due = self.every.is_some_and(|n| iteration.is_multiple_of(n));
+        if !(self.requested.take() || due) {
+            return None;
+        }
+
+        let path = self.dir.join(format!("iteration-{iteration}.json"));
+        let written = fs::create_dir_all(&self.dir)
+            .and_then(|_| File::create(&path))
+            .map_err(crate::Error::from)
+            .and_then(|file| {
+                snapshot::write(egraph, &self.roots, &self.class_data, BufWriter::new(file))
+            });
+        match written {
+            Ok(()) => Some(Value::display(path.display())),
+            Err(e) => {
+                self.error = Some(crate::Error::Snapshot(path, Box::new(e)));
+                None
+            }
+        }
+    }
+
+    fn take_error(&mut self) -> Option<crate::Error> {
+        self.error.take()
+    }
+}
+
+/// Like [`BestProgram`], but records the extracted program itself along with
+/// its `cost`, `size` and `depth`, and whether it `changed` since the last
+/// record. Each is written as its own `best_program/<name>` row.
+pub struct BestExpr<C> {
+    cost_fn: Box<dyn Fn() -> C>,
+    root: egg::Id,
+    last: Option<String>,
+    extractions: Extractions<(Value, String, usize, usize)>,
+}
+
+impl<C> BestExpr<C> {
+    pub fn new(cost_fn: C, root: egg::Id) -> Self
+    where
+        C: Clone + 'static,
+    {
+        BestExpr::new_with(move || cost_fn.clone(), root)
+    }
+
+    pub fn new_with(cost_fn: impl Fn() -> C + 'static, root: egg::Id) -> Self {
+        BestExpr {
+            cost_fn: Box::new(cost_fn),
+            root,
+            last: None,
+            extractions: Extractions::new(),
+        }
+    }
+
+    /// Like [`BestExpr::new_with`], for costs that can only be displayed. See
+    /// [`Displayed`].
+    pub fn displayed(cost_fn: impl Fn() -> C + 'static, root: egg::Id) -> BestExpr<Displayed<C>> {
+        BestExpr::new_with(move || Displayed(cost_fn()), root)
+    }
+
+    pub fn with_extraction(mut self, extraction: Extraction) -> Self {
+        self.extractions.mode = extraction;
+        self
+    }
+
+    /// Only extracts before every `n`th rule application.
+    pub fn sample_every(mut self, n: usize) -> Self {
+        self.extractions.sample_every = n.max(1);
+        self
+    }
+
+    /// A recorder for the time this one spends extracting.
+    pub fn overhead(&self) -> ExtractionOverhead {
+        ExtractionOverhead {
+            spent: self.extractions.spent.clone(),
+        }
+    }
+
+    fn record<L, N>(&mut self, egraph: &egg::EGraph<L, N>) -> Option<Value>
+    where
+        L: egg::Language + std::fmt::Display,
+        N: egg::Analysis<L>,
+        C: egg::CostFunction<L>,
+        C::Cost: Into<Value>,
+    {
+        let (cost_fn, root) = (&self.cost_fn, self.root);
+        let (cost, text, size, depth) = self.extractions.extract(egraph, || {
+            let extractor = egg::Extractor::new(egraph, cost_fn());
+            let (cost, expr) = extractor.find_best(root);
+
+            let nodes = expr.as_ref();
+            let mut depths = vec![0; nodes.len()];
+            for (i, node) in nodes.iter().enumerate() {
+                depths[i] = 1 + node
+                    .children()
+                    .iter()
+                    .map(|child| depths[usize::from(*child)])
+                    .max()
+                    .unwrap_or(0);
+            }
+            let depth = depths.last().copied().unwrap_or(0);
+
+            (cost.into(), expr.to_string(), nodes.len(), depth)
+        });
+
+        let changed = self.last.as_ref() != Some(&text);
+        self.last = Some(text.clone());
+
+        Some(Value::Multi(vec![
+            ("cost".into(), cost),
+            ("expr".into(), text.into()),
+            ("size".into(), size.into()),
+            ("depth".into(), depth.into()),
+            ("changed".into(), changed.into()),
+        ]))
+    }
+}
+
+impl<L, N, C> Recorder<L, N> for BestExpr<C>
+where
+    L: egg::Language + std::fmt::Display,
+    N: egg::Analysis<L>,
+    C: egg::CostFunction<L>,
+    C::Cost: Into<Value>,
+{
+    fn identifier(&self) -> Cow<'static, str> {
+        "best_program".into()
+    }
+
+    fn record_before_rewrite(
+        &mut self,
+        _iteration: usize,
+        egraph: &mut egg::EGraph<L, N>,
+        _rewrite: &egg::Rewrite<L, N>,
+        _matches: &[egg::SearchMatches<L>],
+    ) -> Option<Value> {
+        if !self.extractions.before_rewrite() {
+            return None;
+        }
+        self.record(egraph)
+    }
+
+    fn record_iteration_end(
+        &mut self,
+        _iteration: usize,
+        egraph: &egg::EGraph<L, N>,
+    ) -> Option<Value> {
+        self.record(egraph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distribution(
+        min: usize,
+        median: usize,
+        p90: usize,
+        p99: usize,
+        max: usize,
+        histogram: Vec<usize>,
+    ) -> Option<Value> {
+        Some(Value::multi([
+            ("min", Value::from(min)),
+            ("median", median.into()),
+            ("p90", p90.into()),
+            ("p99", p99.into()),
+            ("max", max.into()),
+            ("histogram", histogram.into()),
+        ]))
+    }
+
+    #[test]
+    fn class_sizes_of_a_single_class() {
+        assert_eq!(class_size_distribution(&[]), None);
+        assert_eq!(
+            class_size_distribution(&[3]),
+            distribution(3, 3, 3, 3, 3, vec![0, 1])
+        );
+    }
+
+    #[test]
+    fn class_sizes_all_equal() {
+        assert_eq!(
+            class_size_distribution(&[4; 10]),
+            distribution(4, 4, 4, 4, 4, vec![0, 0, 10])
+        );
+    }
+
+    #[test]
+    fn class_sizes_skewed() {
+        // the nearest rank of the pth percentile of 100 sizes is the pth size
+        let mut sizes = vec![1; 98];
+        sizes.extend([2, 100]);
+        assert_eq!(
+            class_size_distribution(&sizes),
+            distribution(1, 1, 1, 2, 100, vec![98, 1, 0, 0, 0, 0, 1])
+        );
+
+        // and that of 3 sizes rounds up
+        assert_eq!(
+            class_size_distribution(&[1, 5, 9]),
+            distribution(1, 5, 9, 9, 9, vec![1, 0, 1, 1])
+        );
+    }
 }
@@ -0,0 +1,92 @@
+use std::io::Write;
+
+use crate::{
+    logging_scheduler::DEFAULT_IDENTIFIER,
+    sinks::{CsvSink, SharedSink},
+    LoggingScheduler, Phase, Record, Result, Sink, Value,
+};
+
+/// The output of a set of runs.
+///
+/// Hands out a [`LoggingScheduler`] for every run, all writing to the same
+/// sink, and takes rows written by hand with [`RunLog::row`]. Like
+/// [`SharedSink`], it can be shared between threads.
+#[derive(Clone)]
+pub struct RunLog {
+    sink: SharedSink,
+}
+
+impl RunLog {
+    pub fn new(sink: impl Sink + Send + 'static) -> Self {
+        RunLog {
+            sink: SharedSink::new(sink),
+        }
+    }
+
+    /// Writes csv to `out`, starting with the header line `egg-viz` expects.
+    pub fn csv(out: impl Write + Send + 'static) -> Result<Self> {
+        let mut sink = CsvSink::new(out);
+        sink.write_headers()?;
+        Ok(RunLog::new(sink))
+    }
+
+    /// A scheduler for the next run, with logging enabled. Runs are named
+    /// `run-<n>` unless given an identifier.
+    pub fn scheduler<S, L, N>(&self, scheduler: S) -> LoggingScheduler<'static, S, L, N> {
+        LoggingScheduler::new(scheduler)
+            .with_sink(self.sink.run())
+            .with_logging_enabled(true)
+    }
+
+    /// Starts a row recording `value` under `name`, to be written with
+    /// [`Row::write`].
+    pub fn row(&self, phase: Phase, name: impl ToString, value: impl Into<Value>) -> Row<'_> {
+        Row {
+            log: self,
+            record: Record {
+                id: DEFAULT_IDENTIFIER.to_string(),
+                iteration: 0,
+                rule_name: String::new(),
+                rule: String::new(),
+                phase,
+                recorder: name.to_string(),
+                value: value.into(),
+            },
+        }
+    }
+
+    pub fn flush(&self) -> Result<()> {
+        self.sink.clone().flush()
+    }
+}
+
+/// A row being written by hand to a [`RunLog`].
+#[must_use = "rows are only written by `Row::write`"]
+pub struct Row<'l> {
+    log: &'l RunLog,
+    record: Record,
+}
+
+impl Row<'_> {
+    /// The run the row belongs to.
+    pub fn id(mut self, id: impl ToString) -> Self {
+        self.record.id = id.to_string();
+        self
+    }
+
+    pub fn iteration(mut self, iteration: usize) -> Self {
+        self.record.iteration = iteration;
+        self
+    }
+
+    /// The rule the row is about, by name and as written out.
+    pub fn rule(mut self, name: impl ToString, rule: impl ToString) -> Self {
+        self.record.rule_name = name.to_string();
+        self.record.rule = rule.to_string();
+        self
+    }
+
+    pub fn write(self) -> Result<()> {
+        self.log.sink.clone().write(&self.record)
+    }
+}
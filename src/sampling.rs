@@ -0,0 +1,93 @@
+use std::collections::HashSet;
+
+use regex::Regex;
+
+/// Which parts of a run a [`LoggingScheduler`](crate::LoggingScheduler)
+/// records. By default, everything is recorded.
+#[derive(Clone, Debug)]
+pub struct Sampling {
+    every: usize,
+    rules: Option<RuleFilter>,
+    first: Option<usize>,
+    iterations_only_above: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+enum RuleFilter {
+    Names(HashSet<String>),
+    Matching(Regex),
+}
+
+impl Default for Sampling {
+    fn default() -> Self {
+        Sampling {
+            every: 1,
+            rules: None,
+            first: None,
+            iterations_only_above: None,
+        }
+    }
+}
+
+impl Sampling {
+    pub fn new() -> Self {
+        Sampling::default()
+    }
+
+    /// Only records every `n`th iteration, starting with the first.
+    pub fn every_nth_iteration(mut self, n: usize) -> Self {
+        self.every = n.max(1);
+        self
+    }
+
+    /// Only records searches and applications of the rules named `names`.
+    pub fn rules(mut self, names: impl IntoIterator<Item = impl ToString>) -> Self {
+        self.rules = Some(RuleFilter::Names(
+            names.into_iter().map(|name| name.to_string()).collect(),
+        ));
+        self
+    }
+
+    /// Only records searches and applications of the rules whose name matches
+    /// `pattern`.
+    pub fn rules_matching(mut self, pattern: &str) -> Result<Self, regex::Error> {
+        self.rules = Some(RuleFilter::Matching(Regex::new(pattern)?));
+        Ok(self)
+    }
+
+    /// Only records the first `k` rules searched in every iteration, out of
+    /// those allowed by [`Sampling::rules`]. This counts rules, not rule
+    /// applications: the search of each of those rules is recorded, and so is
+    /// its application, however many matches it has.
+    pub fn first_rules(mut self, k: usize) -> Self {
+        self.first = Some(k);
+        self
+    }
+
+    /// Once the e-graph has more than `enodes` e-nodes at the start of an
+    /// iteration, stops recording searches and applications of rules for the
+    /// rest of the run. The start and end of every iteration are still
+    /// recorded.
+    pub fn iterations_only_above(mut self, enodes: usize) -> Self {
+        self.iterations_only_above = Some(enodes);
+        self
+    }
+
+    pub(crate) fn records_iteration(&self, iteration: usize) -> bool {
+        iteration.is_multiple_of(self.every)
+    }
+
+    pub(crate) fn records_rule(&self, name: &str, recorded_so_far: usize) -> bool {
+        let allowed = match &self.rules {
+            None => true,
+            Some(RuleFilter::Names(names)) => names.contains(name),
+            Some(RuleFilter::Matching(regex)) => regex.is_match(name),
+        };
+        allowed && self.first.is_none_or(|k| recorded_so_far < k)
+    }
+
+    pub(crate) fn iterations_only(&self, enodes: usize) -> bool {
+        self.iterations_only_above
+            .is_some_and(|threshold| enodes > threshold)
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{Phase, Result, Value};
+
+/// A single row of statistics produced by a [`LoggingScheduler`](crate::LoggingScheduler).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    pub id: String,
+    pub iteration: usize,
+    pub rule_name: String,
+    pub rule: String,
+    pub phase: Phase,
+    pub recorder: String,
+    pub value: Value,
+}
+
+/// Somewhere to send [`Record`]s.
+pub trait Sink {
+    fn write(&mut self, record: &Record) -> Result<()>;
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<S: Sink + ?Sized> Sink for Box<S> {
+    fn write(&mut self, record: &Record) -> Result<()> {
+        (**self).write(record)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        (**self).flush()
+    }
+}
+
+impl<S: Sink + ?Sized> Sink for &mut S {
+    fn write(&mut self, record: &Record) -> Result<()> {
+        (**self).write(record)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        (**self).flush()
+    }
+}
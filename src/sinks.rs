@@ -0,0 +1,395 @@
+use std::{
+    cell::RefCell,
+    io::{BufWriter, Write},
+    rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Sender, SyncSender, TrySendError},
+        Arc, Mutex, PoisonError,
+    },
+    thread::JoinHandle,
+};
+
+use crate::{logging_scheduler::DEFAULT_IDENTIFIER, Error, Phase, Record, Result, Sink, Value};
+
+pub(crate) const CSV_HEADERS: [&str; 7] = [
+    "id",
+    "iteration",
+    "rule_name",
+    "rule",
+    "when",
+    "name",
+    "value",
+];
+
+/// Writes records as comma separated lines, in the format `egg-viz` reads.
+///
+/// Fields are quoted following RFC 4180 whenever they contain a comma, a quote
+/// or a newline. Every record is flushed as soon as it is written so that
+/// `egg-viz` can follow the file while a run is in progress, unless the sink
+/// is [`buffered`](CsvSink::buffered). Lists and maps are written as JSON.
+pub struct CsvSink<W: Write> {
+    out: csv::Writer<W>,
+    flush_each: bool,
+}
+
+impl<W: Write> CsvSink<W> {
+    pub fn new(out: W) -> Self {
+        CsvSink {
+            out: csv::WriterBuilder::new()
+                .has_headers(false)
+                .from_writer(out),
+            flush_each: true,
+        }
+    }
+
+    /// Only writes to `out` once a buffer fills up, when flushed, or when the
+    /// sink is dropped.
+    pub fn buffered(out: W) -> Self {
+        CsvSink {
+            flush_each: false,
+            ..CsvSink::new(out)
+        }
+    }
+
+    /// Writes the header line that `egg-viz` expects at the top of a file.
+    pub fn write_headers(&mut self) -> Result<()> {
+        self.out.write_record(CSV_HEADERS)?;
+        self.out.flush()?;
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> Result<W> {
+        self.out.into_inner().map_err(|e| Error::Io(e.into_error()))
+    }
+}
+
+impl<W: Write> Sink for CsvSink<W> {
+    fn write(&mut self, record: &Record) -> Result<()> {
+        self.out.write_record([
+            record.id.as_str(),
+            &record.iteration.to_string(),
+            &record.rule_name,
+            &record.rule,
+            record.phase.as_str(),
+            &record.recorder,
+            &csv_value(&record.value)?,
+        ])?;
+        if self.flush_each {
+            self.out.flush()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Lists and maps are written as JSON so that they can be read back.
+fn csv_value(value: &Value) -> Result<String> {
+    match value {
+        Value::List(_) | Value::Map(_) | Value::Multi(_) => Ok(serde_json::to_string(value)?),
+        value => Ok(value.to_string()),
+    }
+}
+
+/// Writes one JSON object per line. Unlike [`CsvSink`], values keep their
+/// type: numbers stay numbers and lists become arrays. Each object also has a
+/// `type` field naming the [`Value`] variant it came from.
+pub struct JsonLinesSink<W: Write> {
+    out: BufWriter<W>,
+    flush_each: bool,
+}
+
+#[derive(serde::Serialize)]
+struct JsonRecord<'r> {
+    id: &'r str,
+    iteration: usize,
+    rule_name: &'r str,
+    rule: &'r str,
+    when: Phase,
+    name: &'r str,
+    value: &'r Value,
+    #[serde(rename = "type")]
+    typ: &'static str,
+}
+
+impl<W: Write> JsonLinesSink<W> {
+    pub fn new(out: W) -> Self {
+        JsonLinesSink {
+            out: BufWriter::new(out),
+            flush_each: true,
+        }
+    }
+
+    /// Like [`CsvSink::buffered`].
+    pub fn buffered(out: W) -> Self {
+        JsonLinesSink {
+            flush_each: false,
+            ..JsonLinesSink::new(out)
+        }
+    }
+
+    pub fn into_inner(self) -> Result<W> {
+        self.out.into_inner().map_err(|e| Error::Io(e.into_error()))
+    }
+}
+
+impl<W: Write> Sink for JsonLinesSink<W> {
+    fn write(&mut self, record: &Record) -> Result<()> {
+        let mut line = serde_json::to_vec(&JsonRecord {
+            id: &record.id,
+            iteration: record.iteration,
+            rule_name: &record.rule_name,
+            rule: &record.rule,
+            when: record.phase,
+            name: &record.recorder,
+            value: &record.value,
+            typ: record.value.type_name(),
+        })?;
+        line.push(b'\n');
+        self.out.write_all(&line)?;
+        if self.flush_each {
+            self.out.flush()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Keeps records in memory. Clones share the same storage, so keep one around
+/// to read the records back after the run.
+#[derive(Clone, Default)]
+pub struct MemorySink {
+    records: Rc<RefCell<Vec<Record>>>,
+}
+
+impl MemorySink {
+    pub fn new() -> Self {
+        MemorySink::default()
+    }
+
+    pub fn records(&self) -> Vec<Record> {
+        self.records.borrow().clone()
+    }
+
+    pub fn take(&self) -> Vec<Record> {
+        std::mem::take(&mut *self.records.borrow_mut())
+    }
+}
+
+impl Sink for MemorySink {
+    fn write(&mut self, record: &Record) -> Result<()> {
+        self.records.borrow_mut().push(record.clone());
+        Ok(())
+    }
+}
+
+impl Sink for Sender<Record> {
+    fn write(&mut self, record: &Record) -> Result<()> {
+        self.send(record.clone()).map_err(|_| Error::Disconnected)
+    }
+}
+
+impl Sink for SyncSender<Record> {
+    fn write(&mut self, record: &Record) -> Result<()> {
+        self.send(record.clone()).map_err(|_| Error::Disconnected)
+    }
+}
+
+/// What a [`ThreadedSink`] does with a record when its channel is full.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Overflow {
+    /// Wait for the writer thread to catch up.
+    #[default]
+    Block,
+    /// Throw the record away, counting it in [`ThreadedSink::dropped`].
+    Drop,
+}
+
+enum Message {
+    Record(Record),
+    Flush(SyncSender<Result<()>>),
+}
+
+/// Hands records to another sink running on its own thread, over a channel
+/// holding up to `capacity` records, and at least one.
+///
+/// [`flush`](Sink::flush) waits until every record sent before it has been
+/// written and flushed, and returns the first error the writer thread ran
+/// into since the last flush. Dropping the sink also waits for every record
+/// to be written.
+pub struct ThreadedSink {
+    sender: Option<SyncSender<Message>>,
+    thread: Option<JoinHandle<()>>,
+    overflow: Overflow,
+    dropped: Arc<AtomicUsize>,
+}
+
+impl ThreadedSink {
+    pub fn new(sink: impl Sink + Send + 'static, capacity: usize, overflow: Overflow) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(capacity.max(1));
+        let thread = std::thread::spawn(move || {
+            let mut sink = sink;
+            let mut error = None;
+            for message in receiver {
+                match message {
+                    Message::Record(record) => {
+                        if let Err(e) = sink.write(&record) {
+                            error.get_or_insert(e);
+                        }
+                    }
+                    Message::Flush(done) => {
+                        let flushed = sink.flush();
+                        let _ = done.send(error.take().map_or(flushed, Err));
+                    }
+                }
+            }
+            let _ = sink.flush();
+        });
+
+        ThreadedSink {
+            sender: Some(sender),
+            thread: Some(thread),
+            overflow,
+            dropped: Arc::default(),
+        }
+    }
+
+    /// The number of records thrown away so far because the channel was full.
+    pub fn dropped(&self) -> DroppedRecords {
+        DroppedRecords(Arc::clone(&self.dropped))
+    }
+
+    fn send(&self, message: Message) -> Result<()> {
+        let sender = self.sender.as_ref().ok_or(Error::Disconnected)?;
+        sender.send(message).map_err(|_| Error::Disconnected)
+    }
+}
+
+/// Counts the records a [`ThreadedSink`] threw away, see [`Overflow::Drop`].
+#[derive(Clone)]
+pub struct DroppedRecords(Arc<AtomicUsize>);
+
+impl DroppedRecords {
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Sink for ThreadedSink {
+    fn write(&mut self, record: &Record) -> Result<()> {
+        let message = Message::Record(record.clone());
+        match self.overflow {
+            Overflow::Block => self.send(message),
+            Overflow::Drop => {
+                let sender = self.sender.as_ref().ok_or(Error::Disconnected)?;
+                match sender.try_send(message) {
+                    Ok(()) => Ok(()),
+                    Err(TrySendError::Full(_)) => {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        Ok(())
+                    }
+                    Err(TrySendError::Disconnected(_)) => Err(Error::Disconnected),
+                }
+            }
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let (done, flushed) = mpsc::sync_channel(1);
+        self.send(Message::Flush(done))?;
+        flushed.recv().map_err(|_| Error::Disconnected)?
+    }
+}
+
+impl Drop for ThreadedSink {
+    fn drop(&mut self) {
+        // hanging up lets the writer thread finish off the queue
+        self.sender.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// A sink that many threads can write to at once. Records are written one at
+/// a time, so lines from different runs never interleave.
+///
+/// Clones write to the same sink. Give each run its own [`RunSink`] to tell
+/// the runs apart.
+#[derive(Clone)]
+pub struct SharedSink {
+    sink: Arc<Mutex<Box<dyn Sink + Send>>>,
+    runs: Arc<AtomicUsize>,
+}
+
+impl SharedSink {
+    pub fn new(sink: impl Sink + Send + 'static) -> Self {
+        SharedSink {
+            sink: Arc::new(Mutex::new(Box::new(sink))),
+            runs: Arc::default(),
+        }
+    }
+
+    /// A sink for the next run, numbered from 0 in the order they are asked
+    /// for.
+    pub fn run(&self) -> RunSink {
+        let n = self.runs.fetch_add(1, Ordering::Relaxed);
+        RunSink {
+            shared: self.clone(),
+            id: format!("run-{n}"),
+        }
+    }
+}
+
+impl Sink for SharedSink {
+    fn write(&mut self, record: &Record) -> Result<()> {
+        let mut sink = self.sink.lock().unwrap_or_else(PoisonError::into_inner);
+        sink.write(record)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let mut sink = self.sink.lock().unwrap_or_else(PoisonError::into_inner);
+        sink.flush()
+    }
+}
+
+/// One run's share of a [`SharedSink`]. Records of runs that weren't given an
+/// identifier with [`LoggingScheduler::with_identifier`] are written as coming
+/// from `run-<n>`.
+///
+/// [`LoggingScheduler::with_identifier`]: crate::LoggingScheduler::with_identifier
+pub struct RunSink {
+    shared: SharedSink,
+    id: String,
+}
+
+impl RunSink {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl Sink for RunSink {
+    fn write(&mut self, record: &Record) -> Result<()> {
+        if record.id == DEFAULT_IDENTIFIER {
+            self.shared.write(&Record {
+                id: self.id.clone(),
+                ..record.clone()
+            })
+        } else {
+            self.shared.write(record)
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.shared.flush()
+    }
+}
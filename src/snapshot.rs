@@ -0,0 +1,84 @@
+//! Dumps of a whole e-graph in the
+//! [`egraph-serialize`](https://github.com/egraphs-good/egraph-serialize) JSON
+//! format.
+//!
+//! Every e-node is named `<class>.<index>`, and its children point at the
+//! first e-node of each child class, as `egraph-serialize` expects.
+
+use std::{collections::BTreeMap, fmt::Display, io::Write};
+
+use serde::Serialize;
+
+use crate::Result;
+
+#[derive(Serialize)]
+struct SerializedEGraph {
+    nodes: BTreeMap<String, Node>,
+    root_eclasses: Vec<String>,
+    class_data: BTreeMap<String, ClassData>,
+}
+
+#[derive(Serialize)]
+struct Node {
+    op: String,
+    children: Vec<String>,
+    eclass: String,
+    cost: f64,
+}
+
+#[derive(Serialize)]
+struct ClassData {
+    #[serde(rename = "type")]
+    typ: String,
+}
+
+/// Writes `egraph` to `out`.
+///
+/// `class_data` formats the analysis data of each e-class, which is stored as
+/// the class's `type`. Classes it returns `None` for have no `class_data`
+/// entry.
+pub fn write<L, N, W>(
+    egraph: &egg::EGraph<L, N>,
+    roots: &[egg::Id],
+    class_data: impl Fn(&N::Data) -> Option<String>,
+    out: W,
+) -> Result<()>
+where
+    L: egg::Language + Display,
+    N: egg::Analysis<L>,
+    W: Write,
+{
+    let mut nodes = BTreeMap::new();
+    let mut data = BTreeMap::new();
+    for class in egraph.classes() {
+        for (i, node) in class.nodes.iter().enumerate() {
+            nodes.insert(
+                format!("{}.{i}", class.id),
+                Node {
+                    op: node.to_string(),
+                    children: node
+                        .children()
+                        .iter()
+                        .map(|child| format!("{}.0", egraph.find(*child)))
+                        .collect(),
+                    eclass: class.id.to_string(),
+                    cost: 1.0,
+                },
+            );
+        }
+        if let Some(typ) = class_data(&class.data) {
+            data.insert(class.id.to_string(), ClassData { typ });
+        }
+    }
+
+    let serialized = SerializedEGraph {
+        nodes,
+        root_eclasses: roots
+            .iter()
+            .map(|root| egraph.find(*root).to_string())
+            .collect(),
+        class_data: data,
+    };
+    serde_json::to_writer(out, &serialized)?;
+    Ok(())
+}
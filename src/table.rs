@@ -0,0 +1,33 @@
+use std::fmt;
+
+/// Writes `rows` as a table with left-aligned columns, each line starting with
+/// `indent`.
+pub(crate) fn write_table<const N: usize>(
+    f: &mut fmt::Formatter<'_>,
+    indent: &str,
+    rows: &[[String; N]],
+) -> fmt::Result {
+    let mut widths = [0; N];
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    for row in rows {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        writeln!(f, "{indent}{}", line.trim_end())?;
+    }
+    Ok(())
+}
+
+/// Formats a relative change as a percentage, or nothing.
+pub(crate) fn percent(change: Option<f64>) -> String {
+    change
+        .map(|change| format!("{:+.1}%", change * 100.0))
+        .unwrap_or_default()
+}
@@ -0,0 +1,211 @@
+use std::{fmt, time::Duration};
+
+use serde::ser::{SerializeMap, SerializeSeq};
+
+/// A value produced by a [`Recorder`](crate::Recorder).
+///
+/// Durations are written out as (fractional) milliseconds.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+    Duration(Duration),
+    List(Vec<Value>),
+    Map(Vec<(String, Value)>),
+    /// Several values, each written as its own row named
+    /// `<recorder>/<name>`.
+    Multi(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// The name of this value's type, as written to typed outputs and shown in
+    /// `egg-viz`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Int(_) => "int",
+            Value::Float(_) => "float",
+            Value::Bool(_) => "bool",
+            Value::Str(_) => "string",
+            Value::Duration(_) => "duration",
+            Value::List(_) => "list",
+            Value::Map(_) => "map",
+            Value::Multi(_) => "multi",
+        }
+    }
+
+    /// Builds a [`Value::Multi`] out of `(name, value)` pairs.
+    pub fn multi<K, V>(values: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<Value>,
+    {
+        Value::Multi(
+            values
+                .into_iter()
+                .map(|(name, value)| (name.into(), value.into()))
+                .collect(),
+        )
+    }
+
+    /// This value as a number, if it is one. Durations are in milliseconds,
+    /// as they are written out.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Int(n) => Some(*n as f64),
+            Value::Float(n) => Some(*n),
+            Value::Duration(d) => Some(millis(d)),
+            _ => None,
+        }
+    }
+
+    /// The change from `old` to `self`, relative to `old`, if both are numbers
+    /// and `old` isn't zero.
+    pub fn change_from(&self, old: &Value) -> Option<f64> {
+        let (old, new) = (old.as_f64()?, self.as_f64()?);
+        (old != 0.0).then(|| (new - old) / old.abs())
+    }
+
+    /// Wraps anything printable as a [`Value::Str`].
+    pub fn display(value: impl fmt::Display) -> Self {
+        Value::Str(value.to_string())
+    }
+}
+
+fn millis(d: &Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(n) => write!(f, "{n}"),
+            Value::Float(n) => write!(f, "{n}"),
+            Value::Bool(b) => write!(f, "{b}"),
+            Value::Str(s) => write!(f, "{s}"),
+            Value::Duration(d) => write!(f, "{}", millis(d)),
+            Value::List(vs) => {
+                write!(f, "[")?;
+                for (i, v) in vs.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{v}")?;
+                }
+                write!(f, "]")
+            }
+            Value::Map(kvs) | Value::Multi(kvs) => {
+                write!(f, "{{")?;
+                for (i, (k, v)) in kvs.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{k}: {v}")?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+impl serde::Serialize for Value {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Int(n) => serializer.serialize_i64(*n),
+            Value::Float(n) => serializer.serialize_f64(*n),
+            Value::Bool(b) => serializer.serialize_bool(*b),
+            Value::Str(s) => serializer.serialize_str(s),
+            Value::Duration(d) => serializer.serialize_f64(millis(d)),
+            Value::List(vs) => {
+                let mut seq = serializer.serialize_seq(Some(vs.len()))?;
+                for v in vs {
+                    seq.serialize_element(v)?;
+                }
+                seq.end()
+            }
+            Value::Map(kvs) | Value::Multi(kvs) => {
+                let mut map = serializer.serialize_map(Some(kvs.len()))?;
+                for (k, v) in kvs {
+                    map.serialize_entry(k, v)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+macro_rules! from_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Value {
+            fn from(value: $t) -> Self {
+                Value::Int(value as i64)
+            }
+        })*
+    };
+}
+
+from_int!(i8, i16, i32, isize, u8, u16, u32, u64, usize);
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Int(value)
+    }
+}
+
+impl From<f32> for Value {
+    fn from(value: f32) -> Self {
+        Value::Float(value.into())
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Float(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Str(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Str(value.to_string())
+    }
+}
+
+impl From<Duration> for Value {
+    fn from(value: Duration) -> Self {
+        Value::Duration(value)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(value: Vec<T>) -> Self {
+        Value::List(value.into_iter().map(Into::into).collect())
+    }
+}
+
+macro_rules! from_tuple {
+    ($($name:ident),*) => {
+        impl<$($name: Into<Value>),*> From<($($name,)*)> for Value {
+            #[allow(non_snake_case)]
+            fn from(($($name,)*): ($($name,)*)) -> Self {
+                Value::List(vec![$($name.into()),*])
+            }
+        }
+    };
+}
+
+from_tuple!(A, B);
+from_tuple!(A, B, C);
+from_tuple!(A, B, C, D);
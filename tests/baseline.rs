@@ -0,0 +1,74 @@
+use egg_stats::{
+    baseline::{Checks, Outcome},
+    read::{self, Log},
+    Value,
+};
+
+fn log(runs: &[(&str, usize, i64, &str)]) -> Log {
+    let mut csv = String::new();
+    for (id, iterations, cost, stop_reason) in runs {
+        csv += &format!("{id},0,,,iteration_end,cost,{}\n", cost + 1);
+        csv += &format!("{id},1,,,iteration_end,cost,{cost}\n");
+        csv += &format!("{id},{iterations},,,run_end,stop_reason,{stop_reason}\n");
+        csv += &format!("{id},{iterations},,,run_end,iterations,{iterations}\n");
+        csv += &format!("{id},{iterations},,,run_end,final_enodes,1000\n");
+    }
+    read::read_csv(csv.as_bytes()).unwrap()
+}
+
+#[test]
+fn default_checks_catch_regressions() {
+    let baseline = log(&[("a", 5, 10, "saturated"), ("b", 5, 10, "saturated")]);
+
+    let same = Checks::defaults().compare(&baseline, &baseline);
+    assert!(same.passed(), "{same}");
+
+    let worse = log(&[("b", 7, 9, "saturated"), ("a", 5, 11, "node_limit")]);
+    let report = Checks::defaults().compare(&baseline, &worse);
+    let failures: Vec<_> = report
+        .failures()
+        .map(|c| (c.run.as_str(), c.check.metric.as_str()))
+        .collect();
+    assert_eq!(
+        failures,
+        [("a", "cost"), ("a", "stop_reason"), ("b", "iterations")]
+    );
+    assert!(report
+        .comparisons
+        .iter()
+        .filter(|c| c.check.metric == "best_program/cost")
+        .all(|c| c.outcome == Outcome::NotRecorded));
+}
+
+#[test]
+fn missing_runs_fail() {
+    let baseline = log(&[("a", 5, 10, "saturated"), ("b", 5, 10, "saturated")]);
+    let run = log(&[("a", 5, 10, "saturated"), ("c", 5, 10, "saturated")]);
+
+    let report = Checks::new()
+        .within("final_enodes", 0.05)
+        .compare(&baseline, &run);
+    let outcomes: Vec<_> = report.comparisons.iter().map(|c| c.outcome).collect();
+    assert_eq!(outcomes, [Outcome::Passed, Outcome::Failed]);
+}
+
+#[test]
+fn lists_are_only_ordered_when_equal() {
+    let log = |cost: &str| {
+        let csv = format!("a,0,,,iteration_end,cost,\"{cost}\"\n");
+        read::read_csv(csv.as_bytes()).unwrap()
+    };
+    let baseline = log("[3, 1]");
+    assert!(matches!(baseline.last("cost"), Some(Value::List(_))));
+
+    let same = Checks::defaults().compare(&baseline, &baseline);
+    assert!(same.passed(), "{same}");
+
+    let report = Checks::new()
+        .not_above("cost")
+        .same("cost")
+        .compare(&baseline, &log("[2, 5]"));
+    let outcomes: Vec<_> = report.comparisons.iter().map(|c| c.outcome).collect();
+    assert_eq!(outcomes, [Outcome::NotComparable, Outcome::Failed]);
+    assert!(report.to_string().contains("not comparable"));
+}
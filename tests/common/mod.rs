@@ -0,0 +1,58 @@
+#![allow(dead_code)]
+
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+/// A `Write` that can be inspected after the scheduler has been moved into a
+/// runner, or once the threads writing to it are done.
+#[derive(Clone, Default)]
+pub struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    pub fn contents(&self) -> Vec<u8> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// The rows written so far, parsed as csv.
+    pub fn csv_rows(&self) -> Vec<csv::StringRecord> {
+        csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(&self.contents()[..])
+            .records()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+pub fn rules() -> Vec<egg::Rewrite<egg::SymbolLang, ()>> {
+    vec![
+        egg::rewrite!("commute"; "(+ ?a ?b)" => "(+ ?b ?a)"),
+        egg::rewrite!("assoc"; "(+ ?a (+ ?b ?c))" => "(+ (+ ?a ?b) ?c)"),
+    ]
+}
+
+/// Runs [`rules`] on `(+ a (+ b (+ c d)))` for at most `iterations`
+/// iterations.
+pub fn run(
+    scheduler: impl egg::RewriteScheduler<egg::SymbolLang, ()> + 'static,
+    iterations: usize,
+) -> egg::Runner<egg::SymbolLang, ()> {
+    let expr: egg::RecExpr<egg::SymbolLang> = "(+ a (+ b (+ c d)))".parse().unwrap();
+    egg::Runner::default()
+        .with_expr(&expr)
+        .with_iter_limit(iterations)
+        .with_scheduler(scheduler)
+        .run(&rules())
+}
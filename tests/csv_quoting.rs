@@ -0,0 +1,74 @@
+mod common;
+
+use common::SharedBuf;
+use egg::*;
+use egg_stats::{recorders, sinks::CsvSink, LoggingScheduler, StringRecorder};
+use std::borrow::Cow;
+
+define_language! {
+    enum Tricky {
+        "+" = Add([Id; 2]),
+        Symbol(Symbol),
+    }
+}
+
+struct Quoted;
+
+impl<L: Language, N: Analysis<L>> StringRecorder<L, N> for Quoted {
+    fn identifier(&self) -> Cow<'static, str> {
+        "say \"hi\", there".into()
+    }
+
+    fn record_after_rewrite(
+        &self,
+        _iteration: usize,
+        _egraph: &mut EGraph<L, N>,
+        _rewrite: &Rewrite<L, N>,
+        _n_matches: usize,
+    ) -> Option<String> {
+        Some("line one\nline \"two\", three".into())
+    }
+}
+
+#[test]
+fn tricky_fields_round_trip() {
+    let rules: Vec<Rewrite<Tricky, ()>> = vec![
+        rewrite!("comm, \"add\""; "(+ ?a a,b)" => "(+ a,b ?a)"),
+        rewrite!("plain"; "(+ ?a ?b)" => "(+ ?b ?a)"),
+    ];
+    let expr: RecExpr<Tricky> = "(+ x a,b)".parse().unwrap();
+
+    let buf = SharedBuf::default();
+    Runner::default()
+        .with_expr(&expr)
+        .with_iter_limit(2)
+        .with_scheduler(
+            LoggingScheduler::from(SimpleScheduler)
+                .with_identifier("run \"1\", a")
+                .with_sink(CsvSink::new(buf.clone()))
+                .with_logging_enabled(true)
+                .with_recorder(recorders::NumberENodes)
+                .with_recorder(Quoted),
+        )
+        .run(&rules);
+
+    let rows = buf.csv_rows();
+
+    assert!(!rows.is_empty());
+    for row in &rows {
+        assert_eq!(row.len(), 7);
+        assert_eq!(&row[0], "run \"1\", a");
+    }
+
+    let tricky = rows
+        .iter()
+        .find(|row| &row[2] == "comm, \"add\"")
+        .expect("no rows for the tricky rule");
+    assert_eq!(&tricky[3], "(+ ?a a,b) => (+ a,b ?a)");
+
+    let quoted = rows
+        .iter()
+        .find(|row| &row[5] == "say \"hi\", there")
+        .expect("no rows for the quoted recorder");
+    assert_eq!(&quoted[6], "line one\nline \"two\", three");
+}
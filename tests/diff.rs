@@ -0,0 +1,81 @@
+use egg_stats::{diff, read, Value};
+
+const A: &str = "\
+run,0,r1,a => b,after_search,substitutions,3
+run,0,,,iteration_end,enodes,10
+run,0,,,iteration_end,time,1.5
+run,1,r1,a => b,after_search,substitutions,4
+run,1,,,iteration_end,enodes,20
+run,1,,,iteration_end,time,2.5
+run,2,,,run_end,iterations,2
+";
+
+const B: &str = "\
+run,0,r1,a => b,after_search,substitutions,3
+run,0,,,iteration_end,enodes,10
+run,0,,,iteration_end,time,1.7
+run,1,r1,a => b,after_search,substitutions,6
+run,1,,,iteration_end,enodes,25
+run,1,,,iteration_end,time,2.9
+run,2,r1,a => b,after_search,substitutions,1
+run,2,,,iteration_end,enodes,26
+run,3,,,run_end,iterations,3
+";
+
+#[test]
+fn runs_diverge_where_exact_values_differ() {
+    let a = read::read_csv(A.as_bytes()).unwrap();
+    let b = read::read_csv(B.as_bytes()).unwrap();
+    let diff = diff::diff(&a, &b);
+    let [run] = &diff.runs[..] else {
+        panic!("expected one run, got {diff:?}")
+    };
+
+    // `time` differs from the first iteration on, but is a time
+    let (iteration, delta) = run.diverged_at().unwrap();
+    assert_eq!((iteration, delta.name.as_str()), (1, "enodes"));
+
+    let final_enodes = run.finals.iter().find(|d| d.name == "enodes").unwrap();
+    assert_eq!(final_enodes.a, Some(Value::Int(20)));
+    assert_eq!(final_enodes.b, Some(Value::Int(26)));
+
+    let [total] = &run.rules[..] else {
+        panic!("expected one rule, got {:?}", run.rules)
+    };
+    assert_eq!((total.rule.as_str(), total.a, total.b), ("r1", 7, 10));
+}
+
+#[test]
+fn snapshot_paths_never_diverge() {
+    let a = format!("{A}run,0,,,iteration_end,snapshot,a.snapshots/iteration-0.json\n");
+    let b = format!("{B}run,0,,,iteration_end,snapshot,b.snapshots/iteration-0.json\n");
+    let diff = diff::diff(
+        &read::read_csv(a.as_bytes()).unwrap(),
+        &read::read_csv(b.as_bytes()).unwrap(),
+    );
+    let [run] = &diff.runs[..] else {
+        panic!("expected one run, got {diff:?}")
+    };
+
+    let (iteration, delta) = run.diverged_at().unwrap();
+    assert_eq!((iteration, delta.name.as_str()), (1, "enodes"));
+    assert!(!diff.to_string().contains("snapshot per iteration"));
+
+    // the paths still show up among the final values
+    let snapshot = run.finals.iter().find(|d| d.name == "snapshot").unwrap();
+    assert!(snapshot.differs());
+}
+
+#[test]
+fn floats_diverge_unless_they_are_times() {
+    let a = "run,0,,,iteration_end,rule_search_time,1.5\nrun,1,,,iteration_end,cost,2.5\n";
+    let b = "run,0,,,iteration_end,rule_search_time,1.7\nrun,1,,,iteration_end,cost,3.5\n";
+    let diff = diff::diff(
+        &read::read_csv(a.as_bytes()).unwrap(),
+        &read::read_csv(b.as_bytes()).unwrap(),
+    );
+
+    let (iteration, delta) = diff.runs[0].diverged_at().unwrap();
+    assert_eq!((iteration, delta.name.as_str()), (1, "cost"));
+    assert_eq!(delta.a, Some(Value::Float(2.5)));
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use egg::{CostFunction, Id, Language, SimpleScheduler, SymbolLang};
+use egg_stats::{recorders::BestProgram, sinks::MemorySink, LoggingScheduler, Phase, Value};
+use std::fmt;
+
+/// A cost that can be displayed, but not turned into a `Value`.
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+struct Cost(f64);
+
+impl fmt::Display for Cost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.1}", self.0)
+    }
+}
+
+struct Size;
+
+impl CostFunction<SymbolLang> for Size {
+    type Cost = Cost;
+
+    fn cost<C>(&mut self, enode: &SymbolLang, mut costs: C) -> Cost
+    where
+        C: FnMut(Id) -> Cost,
+    {
+        Cost(enode.fold(1.0, |sum, id| sum + costs(id).0))
+    }
+}
+
+#[test]
+fn displayed_costs_are_recorded_as_strings() {
+    let sink = MemorySink::new();
+    let scheduler = LoggingScheduler::from(SimpleScheduler)
+        .with_sink(sink.clone())
+        .with_logging_enabled(true)
+        .with_recorder(BestProgram::displayed(|| Size, Id::from(6)));
+    let handle = scheduler.handle();
+    let runner = common::run(scheduler, 2);
+    handle.finish(&runner);
+
+    let costs: Vec<_> = sink
+        .records()
+        .into_iter()
+        .filter(|record| record.recorder == "cost" && record.phase == Phase::IterationEnd)
+        .map(|record| record.value)
+        .collect();
+    assert!(!costs.is_empty());
+    assert!(costs.iter().all(|cost| *cost == Value::from("7.0")));
+}
@@ -0,0 +1,154 @@
+use std::{cell::Cell, rc::Rc, thread, time::Duration};
+
+use egg::{rewrite, AstSize, EGraph, RecExpr, Runner, SimpleScheduler, SymbolLang};
+use egg_stats::{
+    recorders::{BestProgram, Extraction},
+    sinks::MemorySink,
+    LoggingScheduler, Phase, Record, Value,
+};
+
+/// Runs rules that shrink `(+ 0 (+ 0 (+ 0 a)))` with `best` and its
+/// overhead, and returns every record along with how many times the cost
+/// function was built, once per extraction.
+fn run(
+    best: impl FnOnce(BestProgram<AstSize>) -> BestProgram<AstSize>,
+    extract_time: Duration,
+) -> (Vec<Record>, usize) {
+    let expr: RecExpr<SymbolLang> = "(+ 0 (+ 0 (+ 0 a)))".parse().unwrap();
+    let mut egraph = EGraph::<SymbolLang, ()>::default();
+    let root = egraph.add_expr(&expr);
+
+    let extractions = Rc::new(Cell::new(0));
+    let counted = extractions.clone();
+    let best = best(BestProgram::new_with(
+        move || {
+            counted.set(counted.get() + 1);
+            thread::sleep(extract_time);
+            AstSize
+        },
+        root,
+    ));
+
+    let overhead = best.overhead();
+
+    let sink = MemorySink::new();
+    let scheduler = LoggingScheduler::from(SimpleScheduler)
+        .with_sink(sink.clone())
+        .with_logging_enabled(true)
+        .with_recorder(best)
+        .with_recorder(overhead);
+    let handle = scheduler.handle();
+    let runner = Runner::default()
+        .with_egraph(egraph)
+        .with_iter_limit(4)
+        .with_scheduler(scheduler)
+        .run(&[
+            rewrite!("commute"; "(+ ?a ?b)" => "(+ ?b ?a)"),
+            rewrite!("assoc"; "(+ ?a (+ ?b ?c))" => "(+ (+ ?a ?b) ?c)"),
+            rewrite!("zero"; "(+ 0 ?a)" => "?a"),
+        ]);
+    handle.finish(&runner);
+
+    (sink.records(), extractions.get())
+}
+
+/// The recorded costs, in the order they were recorded.
+fn costs(records: &[Record], phase: Phase) -> Vec<(usize, String, Value)> {
+    records
+        .iter()
+        .filter(|record| record.recorder == "cost" && record.phase == phase)
+        .map(|record| {
+            (
+                record.iteration,
+                record.rule_name.clone(),
+                record.value.clone(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn on_change_reuses_costs_until_the_egraph_changes() {
+    let (every, extractions) = run(|best| best, Duration::ZERO);
+    let (on_change, cached_extractions) = run(
+        |best| best.with_extraction(Extraction::OnChange),
+        Duration::ZERO,
+    );
+
+    for phase in [Phase::BeforeRewrite, Phase::IterationEnd] {
+        assert_eq!(costs(&on_change, phase), costs(&every, phase));
+    }
+    assert!(cached_extractions < extractions);
+
+    // the cost goes down as `zero` applies, so a stale cost would show
+    let mut seen: Vec<_> = costs(&every, Phase::BeforeRewrite)
+        .into_iter()
+        .map(|(_, _, cost)| cost)
+        .collect();
+    seen.dedup();
+    assert!(seen.len() > 1, "{seen:?}");
+}
+
+#[test]
+fn iteration_end_skips_rule_applications() {
+    let (every, _) = run(|best| best, Duration::ZERO);
+    let (records, extractions) = run(
+        |best| best.with_extraction(Extraction::IterationEnd),
+        Duration::ZERO,
+    );
+
+    assert!(costs(&records, Phase::BeforeRewrite).is_empty());
+    let at_end = costs(&records, Phase::IterationEnd);
+    assert_eq!(at_end, costs(&every, Phase::IterationEnd));
+    assert_eq!(extractions, at_end.len());
+}
+
+#[test]
+fn sample_every_extracts_before_every_nth_rule() {
+    let (every, _) = run(|best| best, Duration::ZERO);
+    let (sampled, _) = run(|best| best.sample_every(2), Duration::ZERO);
+
+    let expected: Vec<_> = costs(&every, Phase::BeforeRewrite)
+        .into_iter()
+        .step_by(2)
+        .collect();
+    assert!(expected.len() > 1);
+    assert_eq!(costs(&sampled, Phase::BeforeRewrite), expected);
+    assert_eq!(
+        costs(&sampled, Phase::IterationEnd),
+        costs(&every, Phase::IterationEnd)
+    );
+}
+
+#[test]
+fn overhead_is_the_time_spent_in_each_iteration() {
+    const EXTRACT_TIME: Duration = Duration::from_millis(10);
+    let (records, _) = run(
+        |best| best.with_extraction(Extraction::IterationEnd),
+        EXTRACT_TIME,
+    );
+
+    let times: Vec<_> = records
+        .iter()
+        .filter(|record| record.recorder == "extraction_time")
+        .map(|record| match record.value {
+            Value::Duration(time) => (record.iteration, time),
+            _ => panic!("expected a duration, got {record:?}"),
+        })
+        .collect();
+    let iterations: Vec<_> = costs(&records, Phase::IterationEnd)
+        .into_iter()
+        .map(|(iteration, _, _)| iteration)
+        .collect();
+    assert_eq!(
+        times.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+        iterations
+    );
+    assert!(times.len() >= 3);
+    // one extraction per iteration; by the third, the time would have added up
+    // to three if it wasn't reset
+    for (_, time) in times {
+        assert!(time >= EXTRACT_TIME, "{time:?}");
+        assert!(time < EXTRACT_TIME * 2, "{time:?}");
+    }
+}
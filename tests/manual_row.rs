@@ -0,0 +1,24 @@
+#![allow(deprecated)]
+
+use egg_stats::{manual_row, write_headers};
+
+#[test]
+fn manual_rows_take_any_phase() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("manual_row.csv");
+    write_headers(&path).unwrap();
+    manual_row("run", &path, 3, "", "", "run_end", "verified", true).unwrap();
+    manual_row("run", &path, 3, "", "", "final", "cost, best", 12).unwrap();
+
+    let rows: Vec<Vec<String>> = csv::Reader::from_path(&path)
+        .unwrap()
+        .records()
+        .map(|row| row.unwrap().iter().map(str::to_string).collect())
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            ["run", "3", "", "", "run_end", "verified", "true"],
+            ["run", "3", "", "", "final", "cost, best", "12"],
+        ]
+    );
+}
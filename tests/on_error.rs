@@ -0,0 +1,81 @@
+mod common;
+
+use egg::SimpleScheduler;
+use egg_stats::{
+    recorders, sinks::MemorySink, Error, LoggingScheduler, OnError, Record, Result, Sink,
+};
+use std::{cell::Cell, rc::Rc};
+
+/// A sink that fails every write, counting how many were attempted.
+#[derive(Clone, Default)]
+struct Broken(Rc<Cell<usize>>);
+
+impl Sink for Broken {
+    fn write(&mut self, _: &Record) -> Result<()> {
+        self.0.set(self.0.get() + 1);
+        Err(Error::Disconnected)
+    }
+}
+
+fn scheduler(
+    sink: Broken,
+    on_error: OnError,
+) -> LoggingScheduler<'static, SimpleScheduler, egg::SymbolLang, ()> {
+    LoggingScheduler::from(SimpleScheduler)
+        .with_sink(sink)
+        .with_logging_enabled(true)
+        .with_on_error(on_error)
+        .with_recorder(recorders::NumberENodes)
+}
+
+#[test]
+#[should_panic(expected = "hung up")]
+fn panic_aborts_the_run() {
+    common::run(scheduler(Broken::default(), OnError::Panic), 3);
+}
+
+#[test]
+fn disable_stops_writing_after_the_first_error() {
+    let sink = Broken::default();
+    let scheduler = scheduler(sink.clone(), OnError::Disable);
+    let handle = scheduler.handle();
+    let runner = common::run(scheduler, 3);
+    handle.finish(&runner);
+
+    assert_eq!(sink.0.get(), 1);
+    assert_eq!(handle.take_errors().len(), 1);
+}
+
+#[test]
+fn collect_keeps_every_error() {
+    let sink = Broken::default();
+    let scheduler = scheduler(sink.clone(), OnError::Collect);
+    let handle = scheduler.handle();
+    let runner = common::run(scheduler, 3);
+    handle.finish(&runner);
+
+    assert!(sink.0.get() > 1);
+    assert_eq!(handle.take_errors().len(), sink.0.get());
+}
+
+#[test]
+fn recorder_errors_follow_the_policy() {
+    // snapshots can't be written below a file
+    let file = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("not-a-directory");
+    std::fs::write(&file, "").unwrap();
+
+    let scheduler = LoggingScheduler::from(SimpleScheduler)
+        .with_sink(MemorySink::new())
+        .with_logging_enabled(true)
+        .with_on_error(OnError::Collect)
+        .with_recorder(recorders::Snapshot::new(file.join("snapshots")).every(1));
+    let handle = scheduler.handle();
+    let runner = common::run(scheduler, 2);
+    handle.finish(&runner);
+
+    let errors = handle.take_errors();
+    assert!(!errors.is_empty());
+    assert!(errors
+        .iter()
+        .all(|error| matches!(error, Error::Snapshot(..))));
+}
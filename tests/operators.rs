@@ -0,0 +1,25 @@
+mod common;
+
+use egg::SimpleScheduler;
+use egg_stats::{recorders, sinks::MemorySink, LoggingScheduler, Value};
+
+#[test]
+fn leaves_are_counted_together() {
+    let sink = MemorySink::new();
+    let scheduler = LoggingScheduler::from(SimpleScheduler)
+        .with_sink(sink.clone())
+        .with_logging_enabled(true)
+        .with_recorder(recorders::OperatorCounts);
+    common::run(scheduler, 2);
+
+    let first: Vec<_> = sink
+        .records()
+        .into_iter()
+        .filter(|record| record.iteration == 0 && record.recorder.starts_with("operators/"))
+        .map(|record| (record.recorder, record.value))
+        .collect();
+    let names: Vec<_> = first.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["operators/+", "operators/leaves"]);
+    // `a`, `b`, `c` and `d`
+    assert_eq!(first[1].1, Value::Int(4));
+}
@@ -0,0 +1,29 @@
+mod common;
+
+use egg::SimpleScheduler;
+use egg_stats::{recorders, sinks::MemorySink, LoggingScheduler, Phase, Value, FORMAT_VERSION};
+
+#[test]
+fn after_search_rows_are_labelled_after_search() {
+    let sink = MemorySink::new();
+    let scheduler = LoggingScheduler::from(SimpleScheduler)
+        .with_sink(sink.clone())
+        .with_logging_enabled(true)
+        .with_recorder(recorders::Substitutions);
+    common::run(scheduler, 2);
+
+    let records = sink.records();
+    let first = &records[0];
+    assert_eq!(first.phase, Phase::RunStart);
+    assert_eq!(first.recorder, "format_version");
+    assert_eq!(first.value, Value::Int(FORMAT_VERSION));
+
+    let substitutions: Vec<_> = records
+        .iter()
+        .filter(|record| record.recorder == "substitutions")
+        .collect();
+    assert!(!substitutions.is_empty());
+    assert!(substitutions
+        .iter()
+        .all(|record| record.phase == Phase::AfterSearch));
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use common::SharedBuf;
+use egg::SimpleScheduler;
+use egg_stats::{
+    read, recorders,
+    sinks::{CsvSink, JsonLinesSink},
+    LoggingScheduler, Sink, Value,
+};
+
+fn run(sink: impl Sink + 'static) {
+    let scheduler = LoggingScheduler::from(SimpleScheduler)
+        .with_identifier("read-back")
+        .with_sink(sink)
+        .with_logging_enabled(true)
+        .with_recorder(recorders::NumberENodes)
+        .with_recorder(recorders::ApplyTime::default());
+    let handle = scheduler.handle();
+    // runs until saturated
+    let runner = common::run(scheduler, 30);
+    handle.finish(&runner);
+}
+
+#[test]
+fn csv_and_json_lines_read_back_alike() {
+    let csv = SharedBuf::default();
+    run(CsvSink::new(csv.clone()));
+    let json = SharedBuf::default();
+    run(JsonLinesSink::new(json.clone()));
+
+    let csv = read::read_csv(&csv.contents()[..]).unwrap();
+    let json = read::read_json_lines(&json.contents()[..]).unwrap();
+
+    assert_eq!(csv.runs(), ["read-back"]);
+    assert_eq!(csv.format_version(), egg_stats::FORMAT_VERSION);
+    assert!(!csv.series("enodes").is_empty());
+    assert_eq!(csv.series("enodes"), json.series("enodes"));
+    assert_eq!(
+        csv.by_rule("enodes", egg_stats::Phase::AfterRewrite),
+        json.by_rule("enodes", egg_stats::Phase::AfterRewrite),
+    );
+
+    // durations keep their type in `.jsonl` logs only
+    let apply_times = json.by_rule("rule_apply_time", egg_stats::Phase::AfterRewrite);
+    assert!(matches!(apply_times["commute"][0].1, Value::Duration(_)));
+    // and don't mix with the run's total
+    assert!(json
+        .by_rule("apply_time", egg_stats::Phase::AfterRewrite)
+        .is_empty());
+    let total = json.summaries()[0].get("apply_time").cloned();
+    assert!(matches!(total, Some(Value::Duration(_))));
+
+    let summary = &csv.summaries()[0];
+    assert_eq!(summary.get("stop_reason"), Some(&Value::from("saturated")));
+    assert_eq!(summary.get("final_enodes"), csv.last("enodes"));
+}
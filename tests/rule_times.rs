@@ -0,0 +1,109 @@
+mod common;
+
+use egg::SimpleScheduler;
+use egg_stats::{
+    recorders, sinks::MemorySink, LoggingScheduler, Phase, Record, Result, Sink, Value,
+};
+use std::time::Duration;
+
+const WRITE_TIME: Duration = Duration::from_millis(5);
+
+/// A sink that takes a while to write every record.
+#[derive(Clone, Default)]
+struct Slow(MemorySink);
+
+impl Sink for Slow {
+    fn write(&mut self, record: &Record) -> Result<()> {
+        std::thread::sleep(WRITE_TIME);
+        self.0.write(record)
+    }
+}
+
+#[test]
+fn rule_times_leave_out_writes() {
+    let sink = Slow::default();
+    let scheduler = LoggingScheduler::from(SimpleScheduler)
+        .with_sink(sink.clone())
+        .with_logging_enabled(true)
+        .with_recorder(recorders::NumberENodes)
+        .with_recorder(recorders::NumberEClasses)
+        .with_recorder(recorders::SearchTime::default())
+        .with_recorder(recorders::ApplyTime::default());
+    common::run(scheduler, 2);
+
+    let times: Vec<_> = sink
+        .0
+        .records()
+        .into_iter()
+        .filter(|record| {
+            matches!(
+                record.recorder.as_str(),
+                "rule_search_time" | "rule_apply_time"
+            )
+        })
+        .collect();
+    assert!(times
+        .iter()
+        .any(|record| record.recorder == "rule_search_time"));
+    assert!(times
+        .iter()
+        .any(|record| record.recorder == "rule_apply_time"));
+    for record in times {
+        let Value::Duration(time) = record.value else {
+            panic!("expected a duration, got {record:?}")
+        };
+        assert!(time < WRITE_TIME, "{record:?}");
+    }
+}
+
+#[test]
+fn overhead_is_recorded_once_per_recorder_and_iteration() {
+    let sink = Slow::default();
+    let scheduler = LoggingScheduler::from(SimpleScheduler)
+        .with_sink(sink.clone())
+        .with_logging_enabled(true)
+        .with_overhead_measured(true)
+        .with_recorder(recorders::NumberENodes)
+        .with_recorder(recorders::NumberEClasses);
+    let handle = scheduler.handle();
+    let runner = common::run(scheduler, 2);
+    handle.finish(&runner);
+
+    let records = sink.0.records();
+    for iteration in 0..2 {
+        let overhead: Vec<_> = records
+            .iter()
+            .filter(|record| record.iteration == iteration)
+            .filter(|record| record.recorder.contains("_time/"))
+            .collect();
+        let mut names: Vec<_> = overhead.iter().map(|r| r.recorder.as_str()).collect();
+        names.sort_unstable();
+        assert_eq!(
+            names,
+            [
+                "recorder_time/eclasses",
+                "recorder_time/enodes",
+                "sink_time/eclasses",
+                "sink_time/enodes",
+            ]
+        );
+
+        for record in overhead {
+            assert_eq!(record.phase, Phase::IterationEnd);
+            let Value::Duration(time) = record.value else {
+                panic!("expected a duration, got {record:?}")
+            };
+            let (kind, id) = record.recorder.split_once('/').unwrap();
+            let writes = records
+                .iter()
+                .filter(|r| r.iteration == iteration && r.recorder == id)
+                .count();
+            assert!(writes > 0);
+            match kind {
+                // every write of the recorder's values sleeps
+                "sink_time" => assert!(time >= WRITE_TIME * writes as u32, "{record:?}"),
+                _ => assert!(time < WRITE_TIME, "{record:?}"),
+            }
+        }
+    }
+}
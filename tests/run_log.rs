@@ -0,0 +1,76 @@
+mod common;
+
+use common::SharedBuf;
+use egg::SimpleScheduler;
+use egg_stats::{recorders, Phase, RunLog};
+
+#[test]
+fn runs_share_one_header_and_are_numbered() {
+    let buf = SharedBuf::default();
+    let log = RunLog::csv(buf.clone()).unwrap();
+    for _ in 0..2 {
+        let scheduler = log
+            .scheduler(SimpleScheduler)
+            .with_recorder(recorders::NumberENodes);
+        let handle = scheduler.handle();
+        let runner = common::run(scheduler, 2);
+        handle.finish(&runner);
+    }
+    log.flush().unwrap();
+
+    let rows = buf.csv_rows();
+    let headers: Vec<_> = rows.iter().filter(|row| &row[0] == "id").collect();
+    assert_eq!(headers.len(), 1);
+    assert_eq!(
+        rows[0].iter().collect::<Vec<_>>(),
+        [
+            "id",
+            "iteration",
+            "rule_name",
+            "rule",
+            "when",
+            "name",
+            "value"
+        ]
+    );
+
+    let mut ids: Vec<_> = rows[1..].iter().map(|row| &row[0]).collect();
+    ids.dedup();
+    assert_eq!(ids, ["run-0", "run-1"]);
+}
+
+#[test]
+fn rows_are_written_as_built() {
+    let buf = SharedBuf::default();
+    let log = RunLog::csv(buf.clone()).unwrap();
+    log.row(Phase::RunEnd, "verified", true).write().unwrap();
+    log.row(Phase::AfterRewrite, "note", "slow")
+        .id("run-3")
+        .iteration(2)
+        .rule("commute", "(+ ?a ?b) => (+ ?b ?a)")
+        .write()
+        .unwrap();
+    log.flush().unwrap();
+
+    let rows: Vec<Vec<String>> = buf
+        .csv_rows()
+        .iter()
+        .skip(1)
+        .map(|row| row.iter().map(str::to_string).collect())
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            ["default", "0", "", "", "run_end", "verified", "true"],
+            [
+                "run-3",
+                "2",
+                "commute",
+                "(+ ?a ?b) => (+ ?b ?a)",
+                "after_rewrite",
+                "note",
+                "slow"
+            ],
+        ]
+    );
+}
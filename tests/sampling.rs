@@ -0,0 +1,89 @@
+mod common;
+
+use egg::SimpleScheduler;
+use egg_stats::{recorders, sinks::MemorySink, LoggingScheduler, Phase, Sampling};
+use std::collections::HashSet;
+
+/// The `(iteration, rule, phase)` of every row recorded while running with
+/// `sampling`, leaving out the run's own rows.
+fn rows(sampling: Sampling) -> HashSet<(usize, String, Phase)> {
+    let sink = MemorySink::new();
+    let scheduler = LoggingScheduler::from(SimpleScheduler)
+        .with_sink(sink.clone())
+        .with_logging_enabled(true)
+        .with_sampling(sampling)
+        .with_recorder(recorders::Substitutions)
+        .with_recorder(recorders::NumberENodes);
+    let handle = scheduler.handle();
+    let runner = common::run(scheduler, 4);
+    handle.finish(&runner);
+
+    sink.records()
+        .into_iter()
+        .filter(|record| !matches!(record.phase, Phase::RunStart | Phase::RunEnd))
+        .map(|record| (record.iteration, record.rule_name, record.phase))
+        .collect()
+}
+
+/// The rules recorded in every phase around a rule.
+fn rules(rows: &HashSet<(usize, String, Phase)>) -> HashSet<(usize, &str, Phase)> {
+    rows.iter()
+        .filter(|(_, rule, _)| !rule.is_empty())
+        .map(|(iteration, rule, phase)| (*iteration, rule.as_str(), *phase))
+        .collect()
+}
+
+fn iterations(rows: &HashSet<(usize, String, Phase)>) -> HashSet<usize> {
+    rows.iter().map(|(iteration, _, _)| *iteration).collect()
+}
+
+/// Every rule, searched and applied in every iteration.
+fn all(rules: &[&'static str], iterations: &[usize]) -> HashSet<(usize, &'static str, Phase)> {
+    let phases = [
+        Phase::AfterSearch,
+        Phase::BeforeRewrite,
+        Phase::AfterRewrite,
+    ];
+    iterations
+        .iter()
+        .flat_map(|i| rules.iter().flat_map(move |r| phases.map(|p| (*i, *r, p))))
+        .collect()
+}
+
+#[test]
+fn every_nth_iteration() {
+    let rows = rows(Sampling::new().every_nth_iteration(2));
+    assert_eq!(iterations(&rows), [0, 2].into());
+    assert_eq!(rules(&rows), all(&["commute", "assoc"], &[0, 2]));
+}
+
+#[test]
+fn named_rules() {
+    let rows = rows(Sampling::new().rules(["assoc"]));
+    assert_eq!(iterations(&rows), [0, 1, 2, 3].into());
+    assert_eq!(rules(&rows), all(&["assoc"], &[0, 1, 2, 3]));
+
+    let matching = Sampling::new().rules_matching("^as").unwrap();
+    assert_eq!(self::rows(matching), rows);
+}
+
+#[test]
+fn first_rules_counts_rules_searched() {
+    // rules are searched in the order they are given
+    let rows = rows(Sampling::new().first_rules(1));
+    assert_eq!(rules(&rows), all(&["commute"], &[0, 1, 2, 3]));
+
+    // only allowed rules count
+    let rows = self::rows(Sampling::new().rules(["assoc"]).first_rules(1));
+    assert_eq!(rules(&rows), all(&["assoc"], &[0, 1, 2, 3]));
+}
+
+#[test]
+fn iterations_only_above() {
+    // the e-graph starts out with 7 e-nodes, and grows in the first iteration
+    let rows = rows(Sampling::new().iterations_only_above(7));
+    assert_eq!(rules(&rows), all(&["commute", "assoc"], &[0]));
+    for iteration in 0..4 {
+        assert!(rows.contains(&(iteration, String::new(), Phase::IterationEnd)));
+    }
+}
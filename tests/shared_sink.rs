@@ -0,0 +1,40 @@
+mod common;
+
+use common::SharedBuf;
+use egg::SimpleScheduler;
+use egg_stats::{
+    recorders,
+    sinks::{CsvSink, SharedSink},
+    LoggingScheduler,
+};
+use std::collections::HashSet;
+
+#[test]
+fn parallel_runs_share_one_sink() {
+    let buf = SharedBuf::default();
+    let shared = SharedSink::new(CsvSink::buffered(buf.clone()));
+
+    std::thread::scope(|scope| {
+        for _ in 0..8 {
+            let sink = shared.run();
+            scope.spawn(move || {
+                let scheduler = LoggingScheduler::from(SimpleScheduler)
+                    .with_sink(sink)
+                    .with_logging_enabled(true)
+                    .with_recorder(recorders::NumberENodes);
+                let handle = scheduler.handle();
+                let runner = common::run(scheduler, 4);
+                handle.finish(&runner);
+            });
+        }
+    });
+
+    let rows = buf.csv_rows();
+
+    let ids: HashSet<&str> = rows.iter().map(|row| &row[0]).collect();
+    let expected: HashSet<String> = (0..8).map(|n| format!("run-{n}")).collect();
+    assert_eq!(ids, expected.iter().map(String::as_str).collect());
+    for row in &rows {
+        assert_eq!(row.len(), 7);
+    }
+}
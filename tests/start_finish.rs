@@ -0,0 +1,94 @@
+mod common;
+
+use egg::{EGraph, RecExpr, Runner, SimpleScheduler, SymbolLang};
+use egg_stats::{recorders, sinks::MemorySink, LoggingScheduler, Phase, Record, Value};
+
+fn values(records: &[Record], phase: Phase) -> Vec<(&str, &Value)> {
+    records
+        .iter()
+        .filter(|record| record.phase == phase)
+        .map(|record| (record.recorder.as_str(), &record.value))
+        .collect()
+}
+
+fn get<'r>(values: &[(&str, &'r Value)], name: &str) -> Option<&'r Value> {
+    values
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, value)| *value)
+}
+
+#[test]
+fn start_and_finish_bracket_the_run() {
+    let expr: RecExpr<SymbolLang> = "(+ a (+ b (+ c d)))".parse().unwrap();
+    let mut egraph = EGraph::<SymbolLang, ()>::default();
+    egraph.add_expr(&expr);
+    let rules = common::rules();
+
+    let sink = MemorySink::new();
+    let scheduler = LoggingScheduler::from(SimpleScheduler)
+        .with_sink(sink.clone())
+        .with_logging_enabled(true)
+        .with_git_sha("0123abc")
+        .with_recorder(recorders::NumberENodes);
+    let handle = scheduler.handle();
+    handle.start(&egraph, &rules);
+    let runner = Runner::default()
+        .with_egraph(egraph)
+        .with_iter_limit(2)
+        .with_scheduler(scheduler)
+        .run(&rules);
+
+    let started = sink.records();
+    let start = values(&started, Phase::RunStart);
+    assert_eq!(get(&start, "rules"), Some(&Value::Int(2)));
+    assert_eq!(
+        get(&start, "rule_names"),
+        Some(&Value::from(vec!["commute", "assoc"]))
+    );
+    assert_eq!(get(&start, "initial_enodes"), Some(&Value::Int(7)));
+    assert_eq!(get(&start, "initial_eclasses"), Some(&Value::Int(7)));
+    assert_eq!(
+        get(&start, "scheduler"),
+        Some(&Value::from(std::any::type_name::<SimpleScheduler>()))
+    );
+    assert_eq!(get(&start, "git_sha"), Some(&Value::from("0123abc")));
+
+    // the end of the last iteration is only noticed by `finish`
+    let ended = |records: &[Record]| -> Vec<usize> {
+        let mut ended: Vec<_> = records
+            .iter()
+            .filter(|record| record.phase == Phase::IterationEnd)
+            .map(|record| record.iteration)
+            .collect();
+        ended.dedup();
+        ended
+    };
+    assert_eq!(ended(&sink.records()), [0]);
+    assert!(values(&sink.records(), Phase::RunEnd).is_empty());
+
+    handle.finish(&runner);
+    let records = sink.records();
+    assert_eq!(ended(&records), [0, 1]);
+
+    let end = values(&records, Phase::RunEnd);
+    assert_eq!(
+        get(&end, "stop_reason"),
+        Some(&Value::from("iteration_limit"))
+    );
+    assert_eq!(get(&end, "iterations"), Some(&Value::Int(2)));
+    let final_enodes = runner.egraph.total_size();
+    assert_eq!(get(&end, "final_enodes"), Some(&Value::from(final_enodes)));
+    assert_eq!(
+        get(&end, "final_eclasses"),
+        Some(&Value::from(runner.egraph.number_of_classes()))
+    );
+    for name in ["rebuilds", "total_time", "last_applied", "last_search_time"] {
+        assert!(get(&end, name).is_some(), "{name} missing from {end:?}");
+    }
+    // every run_end row is written in the iteration the run stopped after
+    assert!(records
+        .iter()
+        .filter(|record| record.phase == Phase::RunEnd)
+        .all(|record| record.iteration == 2));
+}
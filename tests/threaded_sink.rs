@@ -0,0 +1,143 @@
+use std::{
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::Duration,
+};
+
+use egg_stats::{
+    sinks::{Overflow, ThreadedSink},
+    Error, Phase, Record, Result, Sink,
+};
+
+fn record(iteration: usize) -> Record {
+    Record {
+        id: "run".to_string(),
+        iteration,
+        rule_name: String::new(),
+        rule: String::new(),
+        phase: Phase::IterationEnd,
+        recorder: "enodes".to_string(),
+        value: iteration.into(),
+    }
+}
+
+/// Tells the test when it starts writing a record, and waits for it to say
+/// when to go on.
+struct Gated {
+    sink: Sender<Record>,
+    started: Sender<()>,
+    go: Receiver<()>,
+}
+
+impl Sink for Gated {
+    fn write(&mut self, record: &Record) -> Result<()> {
+        // the test stops listening once it is done holding the writer up
+        let _ = self.started.send(());
+        let _ = self.go.recv();
+        self.sink.write(record)
+    }
+}
+
+/// A threaded sink stuck writing the first record it was given.
+fn stuck(capacity: usize) -> (ThreadedSink, Receiver<Record>, Sender<()>) {
+    let (sink, written) = mpsc::channel();
+    let (started, has_started) = mpsc::channel();
+    let (go, gate) = mpsc::channel();
+    let mut threaded = ThreadedSink::new(
+        Gated {
+            sink,
+            started,
+            go: gate,
+        },
+        capacity,
+        Overflow::Drop,
+    );
+    threaded.write(&record(0)).unwrap();
+    has_started
+        .recv_timeout(Duration::from_secs(5))
+        .expect("the first record was never written");
+    (threaded, written, go)
+}
+
+#[test]
+fn zero_capacity_still_queues_a_record() {
+    let (mut threaded, written, go) = stuck(0);
+    let dropped = threaded.dropped();
+    threaded.write(&record(1)).unwrap();
+    threaded.write(&record(2)).unwrap();
+    assert_eq!(dropped.count(), 1);
+
+    for _ in 0..2 {
+        go.send(()).unwrap();
+    }
+    drop(go);
+    threaded.flush().unwrap();
+    let iterations: Vec<_> = written.try_iter().map(|r| r.iteration).collect();
+    assert_eq!(iterations, [0, 1]);
+}
+
+#[test]
+fn overflowing_records_are_counted() {
+    let (mut threaded, written, go) = stuck(2);
+    let dropped = threaded.dropped();
+    for iteration in 1..=5 {
+        threaded.write(&record(iteration)).unwrap();
+    }
+    assert_eq!(dropped.count(), 3);
+
+    drop(go);
+    threaded.flush().unwrap();
+    let iterations: Vec<_> = written.try_iter().map(|r| r.iteration).collect();
+    assert_eq!(iterations, [0, 1, 2]);
+    assert_eq!(dropped.count(), 3);
+}
+
+/// Fails to write the record of iteration 1.
+struct Failing(Sender<Record>);
+
+impl Sink for Failing {
+    fn write(&mut self, record: &Record) -> Result<()> {
+        if record.iteration == 1 {
+            return Err(Error::Disconnected);
+        }
+        self.0.write(record)
+    }
+}
+
+#[test]
+fn flush_returns_the_writer_error() {
+    let (sink, written) = mpsc::channel();
+    let mut threaded = ThreadedSink::new(Failing(sink), 8, Overflow::Block);
+    for iteration in 0..3 {
+        threaded.write(&record(iteration)).unwrap();
+    }
+    assert!(matches!(threaded.flush(), Err(Error::Disconnected)));
+    // the error is only reported once, and the thread keeps writing
+    threaded.write(&record(3)).unwrap();
+    threaded.flush().unwrap();
+
+    let iterations: Vec<_> = written.try_iter().map(|r| r.iteration).collect();
+    assert_eq!(iterations, [0, 2, 3]);
+}
+
+struct Slow(Sender<Record>);
+
+impl Sink for Slow {
+    fn write(&mut self, record: &Record) -> Result<()> {
+        thread::sleep(Duration::from_millis(1));
+        self.0.write(record)
+    }
+}
+
+#[test]
+fn dropping_waits_for_every_record() {
+    let (sink, written) = mpsc::channel();
+    let mut threaded = ThreadedSink::new(Slow(sink), 4, Overflow::Block);
+    for iteration in 0..20 {
+        threaded.write(&record(iteration)).unwrap();
+    }
+    drop(threaded);
+
+    let iterations: Vec<_> = written.try_iter().map(|r| r.iteration).collect();
+    assert_eq!(iterations, (0..20).collect::<Vec<_>>());
+}